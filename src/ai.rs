@@ -2,7 +2,7 @@
 
 use core::ops::{Deref, DerefMut};
 use proc_bitfield::bitfield;
-use crate::{RW, WO};
+use crate::{cp0, ShadowReg, RW, WO};
 
 /// A wrapper around a mutable reference to the Audio Interface's memory mapped registers.
 /// 
@@ -56,13 +56,207 @@ impl DerefMut for AudioInterface {
 
 regfn_wo!(AudioInterface, dram_addr, DRAM_ADDR, u32);
 regfn_rw!(AudioInterface, length, LENGTH, u32);
-regfn_wo!(AudioInterface, control, CONTROL, u32);
 regfn_rw!(AudioInterface, status, STATUS, StatusReg);
-regfn_wo!(AudioInterface, dac_rate, DAC_RATE, u32);
-regfn_wo!(AudioInterface, bit_rate, BIT_RATE, u32);
+
+// The write-only registers are written through shadows, so they can be modified. Writing them
+// directly through `RegisterBlock` bypasses the shadows. `AI_LENGTH` isn't shadowed, as it can be
+// read back (returning the remaining length of the current buffer).
+
+/// Shadow of the write-only `AI_CONTROL` register, used by [`set_control()`] and [`modify_control()`].
+/// 
+/// Assumes the register is 0 (DMA disabled) until first written.
+pub static CONTROL_SHADOW: ShadowReg<u32> = unsafe { ShadowReg::new(0xA4500008 as *mut WO<u32>, 0) };
+/// Shadow of the write-only `AI_DACRATE` register, used by [`set_dac_rate()`] and [`modify_dac_rate()`].
+/// 
+/// Assumes the register is 0 until first written.
+pub static DAC_RATE_SHADOW: ShadowReg<u32> = unsafe { ShadowReg::new(0xA4500010 as *mut WO<u32>, 0) };
+/// Shadow of the write-only `AI_BITRATE` register, used by [`set_bit_rate()`] and [`modify_bit_rate()`].
+/// 
+/// Assumes the register is 0 until first written.
+pub static BIT_RATE_SHADOW: ShadowReg<u32> = unsafe { ShadowReg::new(0xA4500014 as *mut WO<u32>, 0) };
+
+macro_rules! ai_shadowed_wo {
+    ($reg:ident, $reg_name:expr, $shadow:ident) => {
+        paste::paste! {
+            #[doc = concat!("Writes data to the ", stringify!($reg_name), " register, through [`", stringify!($shadow), "`].")]
+            #[inline(always)]
+            pub unsafe fn [<set_ $reg>](data: u32) {
+                $shadow.write(data);
+            }
+            
+            #[doc = concat!("Modifies the last value written to the ", stringify!($reg_name), " register, then writes it back, through [`", stringify!($shadow), "`].")]
+            #[inline(always)]
+            pub unsafe fn [<modify_ $reg>]<F: FnOnce(u32) -> u32>(func: F) {
+                $shadow.modify(func);
+            }
+        }
+    }
+}
+ai_shadowed_wo!(control, CONTROL, CONTROL_SHADOW);
+ai_shadowed_wo!(dac_rate, DAC_RATE, DAC_RATE_SHADOW);
+ai_shadowed_wo!(bit_rate, BIT_RATE, BIT_RATE_SHADOW);
+
+monitor_table!("AI", [
+    monitor_reg!(AudioInterface, dram_addr, wo),
+    monitor_reg!(AudioInterface, length, rw),
+    monitor_reg!(AudioInterface, control, wo),
+    monitor_reg!(AudioInterface, status, rw, StatusReg),
+    monitor_reg!(AudioInterface, dac_rate, wo),
+    monitor_reg!(AudioInterface, bit_rate, wo),
+]);
+
+/// Maximum number of bytes in a single AI DMA buffer.
+/// 
+/// The `AI_LENGTH` register holds 18 bits, and the lowest 3 bits are ignored.
+pub const DMA_MAX_LEN: u32 = 0x3FFF8;
+
+/// Queues a buffer of `len` bytes for playback, without checking whether the DMA FIFO has space.
+/// 
+/// `dram` is the RDRAM address of the buffer (8-byte aligned), and may be given as a physical,
+/// KSEG0, or KSEG1 address. `len` must be a multiple of 8, and at most [`DMA_MAX_LEN`].
+#[inline(always)]
+pub unsafe fn submit(dram: u32, len: u32) {
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_length(len);
+}
+
+/// Queues a buffer of `len` bytes for playback, if the DMA FIFO has space.
+/// 
+/// The AI's DMA FIFO is two entries deep: one buffer is being played, and one more can be queued
+/// behind it. Once both entries are in use, `status().full()` is set, and writing another buffer
+/// would replace the queued one, so it is never played. As soon as the playing buffer finishes,
+/// the queued one starts, and an entry frees up.
+/// 
+/// The full bit is checked and the buffer submitted with interrupts disabled, so an interrupt
+/// handler submitting its own buffer can't fill the FIFO in between. Returns [`AiBusy`] if the
+/// FIFO was full, in which case nothing was written.
+/// 
+/// See [`submit()`] for the requirements of each parameter.
+pub unsafe fn try_submit(dram: u32, len: u32) -> Result<(), AiBusy> {
+    cp0::interrupt_free(|| {
+        if status().full() {
+            Err(AiBusy)
+        } else {
+            submit(dram, len);
+            Ok(())
+        }
+    })
+}
+
+/// Queues a buffer of any length for playback, splitting it into multiple DMA buffers of at most
+/// [`DMA_MAX_LEN`] bytes.
+/// 
+/// Before each part is queued, this waits until the DMA FIFO has space, so this blocks until the
+/// last part has been queued. See [`submit()`] for the alignment requirements.
+pub unsafe fn submit_large(dram: u32, len: u32) {
+    let mut offset = 0;
+    while offset < len {
+        let chunk = (len - offset).min(DMA_MAX_LEN);
+        while status().full() {}
+        submit(dram + offset, chunk);
+        offset += chunk;
+    }
+}
+
+/// Enables or disables AI DMA, by writing bit 0 of `AI_CONTROL` through [`CONTROL_SHADOW`].
+/// 
+/// While disabled, queued buffers stay in the DMA FIFO and the DAC outputs silence; enabling resumes
+/// playback from where it stopped. Toggling the enable while a buffer is playing cuts it off
+/// mid-sample, which is audible as a click, so it's best done while the FIFO is empty.
+#[inline(always)]
+pub unsafe fn set_dma_enable(enable: bool) {
+    CONTROL_SHADOW.modify(|control| (control & !1) | enable as u32);
+}
+
+/// Returns whether AI DMA was last enabled through [`set_dma_enable()`] (or any other write through
+/// [`CONTROL_SHADOW`]).
+/// 
+/// This reads the shadow rather than the hardware, so it's only valid if `AI_CONTROL` is never
+/// written directly through [`RegisterBlock`], and the shadow's initial value (disabled) matches
+/// the hardware. Code taking over from a bootloader which may have left DMA enabled should call
+/// [`set_dma_enable()`] once at startup. `status().dma_enable()` reports the hardware's state, at
+/// the cost of an uncached register read.
+#[must_use]
+#[inline(always)]
+pub fn is_dma_enabled() -> bool {
+    CONTROL_SHADOW.read() & 1 != 0
+}
+
+/// Complete AI configuration, written by [`configure()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    /// Written to `AI_DACRATE` (14 bits): the video clock divided by the sample rate, minus one.
+    pub dac_rate: u32,
+    /// Written to `AI_BITRATE` (4 bits): the DAC rate divided by the bit clock rate, minus one.
+    pub bit_rate: u8,
+    /// Whether DMA is enabled once the rates have been written.
+    pub dma_enable: bool,
+}
+impl Config {
+    /// Checks that every value fits in its register.
+    pub fn validate(&self) -> Result<(), AiConfigError> {
+        if self.dac_rate == 0 || self.dac_rate > 0x3FFF {
+            Err(AiConfigError::DacRateOutOfRange)
+        } else if self.bit_rate > 0xF {
+            Err(AiConfigError::BitRateOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Invalid [`Config`] values, reported by [`configure()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AiConfigError {
+    /// The DAC rate is zero, or doesn't fit in 14 bits.
+    DacRateOutOfRange,
+    /// The bit rate doesn't fit in 4 bits.
+    BitRateOutOfRange,
+}
+
+/// Validates `config`, then writes it in the order the AI requires.
+/// 
+/// DMA is disabled first, so no buffer plays while the clocks change, then `AI_DACRATE` and
+/// `AI_BITRATE` are written, and DMA is enabled last (if requested). Queued buffers are kept, and
+/// resume playing at the new rate. Nothing is written if the config is invalid.
+pub unsafe fn configure(config: &Config) -> Result<(), AiConfigError> {
+    config.validate()?;
+    
+    set_dma_enable(false);
+    set_dac_rate(config.dac_rate);
+    set_bit_rate(config.bit_rate as u32);
+    set_dma_enable(config.dma_enable);
+    
+    Ok(())
+}
+
+/// Reads the DAC counter field of `AI_STATUS`. See [`StatusReg::dac_counter()`].
+#[must_use]
+#[inline(always)]
+pub fn dac_counter() -> u16 {
+    status().dac_counter()
+}
+
+/// Returns the number of stereo 16-bit samples remaining in the buffer currently being played.
+/// 
+/// Reading `AI_LENGTH` returns the number of bytes of the current buffer which haven't yet been
+/// transferred to the DAC, and each sample frame is 4 bytes. This is the reliable way to find the
+/// playback position within a buffer; subtracting it from the buffer's length gives the number of
+/// samples elapsed.
+#[must_use]
+#[inline(always)]
+pub fn remaining_samples() -> u32 {
+    length() / 4
+}
+
+/// Returned by [`try_submit()`] when the AI's DMA FIFO is full.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct AiBusy;
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct StatusReg(pub u32): Debug {
         pub clear_interrupt: u32 [wo] @ ..,
         
@@ -78,4 +272,19 @@ bitfield! {
         pub dma_busy: bool [ro] @ 27,
         pub busy: bool [ro] @ 30,
     }
-}
\ No newline at end of file
+}
+impl_pod!(StatusReg);
+impl StatusReg {
+    /// Returns the DAC counter, which advances once per sample clocked out to the DAC.
+    /// 
+    /// This is a free-running 14-bit counter which wraps, and isn't reset when a new buffer starts,
+    /// so it counts samples _elapsed_ (modulo `0x4000`), not samples remaining or the position within
+    /// the current buffer. The difference between two readings gives the number of samples played
+    /// in between, as long as fewer than `0x4000` samples passed. For the position within the
+    /// current buffer, use [`remaining_samples()`].
+    #[must_use]
+    #[inline(always)]
+    pub fn dac_counter(&self) -> u16 {
+        self.dac_cntr()
+    }
+}
@@ -1,16 +1,26 @@
 //! CPU - Coprocessor 0
 
+#[cfg(target_arch = "mips")]
 use core::arch::asm;
+use core::fmt;
 use core::marker::PhantomData;
+use core::ops::Range;
 use num_enum::{FromPrimitive, IntoPrimitive};
 use proc_bitfield::bitfield;
 
+pub mod profile;
+pub mod timer;
+pub mod tlb;
+
+pub use timer::{Delay, Duration, Instant};
+
 //TODO: Complete rustdocs for all bitfields
 
 macro_rules! cp0fn_ro {
     ($reg:ident, $width:ident, $index:literal, $datatype:ident) => {
         paste::paste! {
             #[doc = concat!("Reads from CP0 register ", stringify!($index), ".")]
+            #[must_use]
             #[inline(always)]
             pub fn $reg() -> $datatype {
                 [<read_ $width>]::<$index>().into()
@@ -46,6 +56,11 @@ macro_rules! cp0fn_rw {
 
 /// A zero-sized struct for accessing CP0 registers via methods.
 /// 
+/// Every public function defined directly in the [module][crate::cp0] has a method of the same
+/// name, and the [`tlb`] functions have methods prefixed with `tlb_`. The [`timer`] and [`profile`]
+/// submodules aren't mirrored. Methods are `unsafe` where the function has requirements beyond
+/// register access.
+/// 
 /// See [`Cp0::new()`] for usage details.
 pub struct Cp0 {
     _marker: PhantomData<*const ()>
@@ -57,8 +72,12 @@ impl Cp0 {
     /// But for unrestricted, unsafe, access, this struct provides a method-based version to the
     /// static functions available at the [module][crate::cp0] level.
     /// 
+    /// Reading CP0 registers is always safe, so read-only code doesn't need this struct at all; the
+    /// read functions at the [module][crate::cp0] level are safe to call. Only the module level
+    /// write and modify functions are `unsafe`.
+    /// 
     /// # Safety
-    /// This provides unrestricted access to memory mapped registers. Data races _could_ occur if writing
+    /// This provides unrestricted write access to CP0 registers. Data races _could_ occur if writing
     /// to a register in both regular code and inside interrupt handlers.
     /// 
     /// This is especially problematic if performing a read-modify-write operation; an interrupt
@@ -90,8 +109,48 @@ impl Cp0 {
     cpxmethod_rw!(watchhi, WatchHiReg);
     cpxmethod_rw!(xcontext, XContextReg);
     cpxmethod_rw!(parity_error, ParityErrorReg);
+    cpxmethod_ro!(cache_err, CacheErrReg);
     cpxmethod_rw!(taglo, TagLoReg);
     cpxmethod_rw!(error_exception_pc, ErrorExceptionPcReg);
+    
+    /// Returns an iterator which reads each of the 32 TLB entries. See [`tlb_entries()`].
+    /// 
+    /// # Safety
+    /// See [`tlb_entries()`].
+    pub unsafe fn tlb_entries(&self) -> TlbEntries {
+        tlb_entries()
+    }
+    
+    cpxmethod_fn!(#[must_use] fn endianness() -> Endianness);
+    cpxmethod_fn!(unsafe fn set_endianness(endianness: Endianness));
+    cpxmethod_fn!(unsafe fn tlb_probe());
+    cpxmethod_fn!(unsafe fn tlb_write_indexed());
+    cpxmethod_fn!(unsafe fn tlb_read());
+    cpxmethod_fn!(unsafe fn tlb_write_random());
+    cpxmethod_fn!(unsafe fn eret() -> !);
+    cpxmethod_fn!(unsafe fn enter_user_mode(pc: u64) -> !);
+    cpxmethod_fn!(unsafe fn refill_from_context(page_table_base: u32));
+    cpxmethod_fn!(unsafe fn xrefill_from_xcontext(page_table_base: u64));
+    cpxmethod_fn!(#[must_use] unsafe fn find_tlb_entry(vaddr: u64, asid: u8) -> Option<u8>);
+    cpxmethod_fn!(unsafe fn lock_tlb_entries(count: u8) -> Range<u8>);
+    cpxmethod_fn!(#[must_use] unsafe fn read_tlb_entry(index: u8) -> TlbEntry);
+    cpxmethod_fn!(#[must_use] fn exception_info() -> ExceptionInfo);
+    cpxmethod_fn!(#[must_use] fn cache_error_info() -> CacheError);
+    cpxmethod_fn!(fn on_coprocessor_unusable() -> u8);
+    cpxmethod_fn!(#[must_use] fn fpu_enabled() -> bool);
+    cpxmethod_fn!(#[must_use] fn reset_pending() -> bool);
+    cpxmethod_fn!(unsafe fn defer_fpu());
+    cpxmethod_fn!(unsafe fn enable_fpu());
+    cpxmethod_fn!(fn interrupt_free<F: FnOnce() -> R, R>(func: F) -> R);
+    cpxmethod_fn!(#[must_use] fn cpu_clock_hz() -> Option<u32>);
+    cpxmethod_fn!(#[must_use] fn read_u32<const INDEX: u32>() -> u32);
+    cpxmethod_fn!(#[must_use] fn read_u64<const INDEX: u32>() -> u64);
+    cpxmethod_fn!(unsafe fn write_u32<const INDEX: u32>(value: u32));
+    cpxmethod_fn!(unsafe fn write_u64<const INDEX: u32>(value: u64));
+    cpxmethod_fn!(unsafe fn tlb_map(vaddr: u64, paddr: u32, size: PageSize, cache: CacheAlgorithm, flags: tlb::PageFlags) -> Result<u8, tlb::TlbError> => tlb::map);
+    cpxmethod_fn!(unsafe fn tlb_map_at(index: u8, vaddr: u64, paddr: u32, size: PageSize, cache: CacheAlgorithm, flags: tlb::PageFlags) -> Result<(), tlb::TlbError> => tlb::map_at);
+    cpxmethod_fn!(unsafe fn tlb_unmap(vaddr: u64) -> Result<(), tlb::TlbError> => tlb::unmap);
+    cpxmethod_fn!(unsafe fn tlb_clear() => tlb::clear);
 }
 
 cp0fn_rw!(index, u32, 0, IndexReg);
@@ -109,32 +168,488 @@ cp0fn_rw!(status, u32, 12, StatusReg);
 cp0fn_rw!(cause, u32, 13, CauseReg);
 cp0fn_rw!(exception_pc, u64, 14, ExceptionPcReg);
 cp0fn_ro!(processor_revision_id, u32, 15, ProcessorRevisionIdReg);
-cp0fn_rw!(config, u32, 16, ConfigReg);
+cp0fn_ro!(config, u32, 16, ConfigReg);
 cp0fn_rw!(load_linked_address, u32, 17, u32);
 cp0fn_rw!(watchlo, u32, 18, WatchLoReg);
 cp0fn_rw!(watchhi, u32, 19, WatchHiReg);
 cp0fn_rw!(xcontext, u64, 20, XContextReg);
 cp0fn_rw!(parity_error, u32, 26, ParityErrorReg);
+cp0fn_ro!(cache_err, u32, 27, CacheErrReg);
 cp0fn_rw!(taglo, u32, 28, TagLoReg);
 cp0fn_rw!(error_exception_pc, u64, 30, ErrorExceptionPcReg);
 
+/// Writes to CP0 register 16, preserving the current value of the [`be`][ConfigReg::be()] bit.
+/// 
+/// The `be` bit of `data` is ignored, as changing the endianness would corrupt execution. Use
+/// [`set_endianness()`] if that's really intended.
+#[inline(always)]
+pub unsafe fn set_config(data: ConfigReg) {
+    write_u32::<16>(data.with_be(config().be()).into());
+}
+
+/// Reads from CP0 register 16, modifies the data, then writes it back into the register, preserving
+/// the current value of the [`be`][ConfigReg::be()] bit. See [`set_config()`].
+#[inline(always)]
+pub unsafe fn modify_config<F: FnOnce(ConfigReg) -> ConfigReg>(func: F) {
+    set_config(func(config()));
+}
+
+/// Byte order used by the CPU for memory accesses, selected by the [`be`][ConfigReg::be()] bit of
+/// the Config register.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Returns the byte order currently used by the CPU. This is always [`Endianness::Big`] on the N64,
+/// unless changed by [`set_endianness()`].
+#[must_use]
+#[inline(always)]
+pub fn endianness() -> Endianness {
+    if config().be() { Endianness::Big } else { Endianness::Little }
+}
+
+/// Changes the byte order used by the CPU for memory accesses.
+/// 
+/// # Safety
+/// **This will almost certainly crash the program.** Every memory access made after the write uses
+/// the new byte order, including instruction fetches, the stack, and every static, while all code
+/// and data in memory remain laid out in the old byte order. The RCP is always big endian, so memory
+/// mapped registers and DMA buffers would also be accessed incorrectly.
+/// 
+/// Only code specifically written and loaded for the new byte order, with no live data in memory,
+/// can survive this. It should normally only be done at boot, if at all.
+#[inline(always)]
+pub unsafe fn set_endianness(endianness: Endianness) {
+    write_u32::<16>(config().with_be(endianness == Endianness::Big).into());
+}
+
+/// Executes the `tlbp` instruction, searching the TLB for an entry matching the current EntryHi
+/// register.
+/// 
+/// If a match is found, its index is written to the Index register. Otherwise the Index register's
+/// `probe` bit is set.
+#[inline(always)]
+pub unsafe fn tlb_probe() {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        tlbp
+        nop
+        nop
+    ");
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(());
+}
+
+/// Executes the `tlbwi` instruction, writing the current EntryHi, EntryLo0, EntryLo1, and PageMask
+/// registers into the TLB entry selected by the Index register.
+#[inline(always)]
+pub unsafe fn tlb_write_indexed() {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        tlbwi
+        nop
+        nop
+    ");
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(());
+}
+
+/// Executes the `tlbr` instruction, reading the TLB entry selected by the Index register into the
+/// EntryHi, EntryLo0, EntryLo1, and PageMask registers.
+#[inline(always)]
+pub unsafe fn tlb_read() {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        tlbr
+        nop
+        nop
+    ");
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(());
+}
+
+/// Executes the `tlbwr` instruction, writing the current EntryHi, EntryLo0, EntryLo1, and PageMask
+/// registers into the TLB entry selected by the Random register.
+/// 
+/// Random never selects an index below the value of the Wired register.
+#[inline(always)]
+pub unsafe fn tlb_write_random() {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        tlbwr
+        nop
+        nop
+    ");
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(());
+}
+
+/// Executes the `eret` instruction, returning from an exception.
+/// 
+/// If Status.erl is set, `eret` clears it and jumps to ErrorEPC. Otherwise it clears Status.exl and
+/// jumps to EPC. Once both are clear, execution continues in the mode selected by Status.ksu, with
+/// interrupts enabled according to Status.ie. The LLbit is also cleared, causing any pending `sc`
+/// to fail. `eret` has no delay slot.
+#[inline(always)]
+pub unsafe fn eret() -> ! {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        eret
+    ", options(noreturn));
+    #[cfg(not(target_arch = "mips"))]
+    {
+        crate::host_unsupported::<_, ()>(());
+        unreachable!()
+    }
+}
+
+/// Drops to user mode, and jumps to `pc`.
+/// 
+/// Status.ksu is set to user mode while Status.exl is set (keeping the CPU in kernel mode, with
+/// interrupts disabled), EPC is set to `pc`, and then [`eret()`] clears exl to perform the switch.
+/// Status.erl is cleared so that `eret` uses EPC. Status.ie is left unchanged, and takes effect once
+/// user code starts.
+/// 
+/// User mode can only access the 2GB `useg`/`xuseg` region (`0x00000000..0x80000000` in 32-bit
+/// mode), which is always mapped through the TLB. `pc`, and any memory the user code accesses,
+/// must have been mapped beforehand. The only way back to kernel mode is through an exception, such
+/// as `syscall`, which enters the general exception vector.
+pub unsafe fn enter_user_mode(pc: u64) -> ! {
+    set_status(status().with_exl(true).with_erl(false).with_ksu(2));
+    set_exception_pc(ExceptionPcReg(pc));
+    eret()
+}
+
+/// Loads a pair of page table entries into EntryLo0/EntryLo1, and writes them to a random TLB entry.
+/// 
+/// The low 32 bits of each 64-bit entry are the EntryLo value.
+#[inline(always)]
+unsafe fn refill_from_ptes(ptes: *const [u64; 2]) {
+    let [even, odd] = ptes.read_volatile();
+    set_entrylo0(EntryLoReg(even as u32));
+    set_entrylo1(EntryLoReg(odd as u32));
+    tlb_write_random();
+}
+
+/// Handles a 32-bit TLB refill exception, using the Context register to index a page table.
+/// 
+/// The page table holds one 16-byte entry per pair of virtual pages (VPN2): the EntryLo0 value for
+/// the even page, followed by the EntryLo1 value for the odd page, each stored in the low 32 bits of
+/// a 64-bit word. Context.BadVPN2 is already scaled to this entry size, so the entry's address is
+/// `page_table_base + (BadVPN2 << 4)`. EntryHi is filled in by the CPU when the exception occurs.
+/// 
+/// The TLB refill vector (`0x80000000`, or `0xBFC00200` when Status.BEV is set) is used for misses
+/// in 32-bit address regions.
+/// 
+/// # Safety
+/// Must only be called from the TLB refill exception handler, with PageMask set to the page size
+/// used by the page table, and `page_table_base` pointing to a valid page table.
+#[inline(always)]
+pub unsafe fn refill_from_context(page_table_base: u32) {
+    let offset = (context().bad_vpn2() as u32) << 4;
+    refill_from_ptes(page_table_base.wrapping_add(offset) as usize as *const [u64; 2]);
+}
+
+/// Handles a 64-bit (XTLB) refill exception, using the XContext register to index a page table.
+/// 
+/// The page table uses the same 16-byte entry format as [`refill_from_context()`], but is indexed
+/// by both XContext.R (the faulting address's region) and XContext.BadVPN2, so the entry's address
+/// is `page_table_base + (R << 31) + (BadVPN2 << 4)`.
+/// 
+/// The XTLB refill vector (`0x80000080`, or `0xBFC00280` when Status.BEV is set) is only used when
+/// the faulting address is in a 64-bit region, as selected by Status.KX, Status.SX, and Status.UX
+/// for kernel, supervisor, and user addresses respectively. Otherwise the 32-bit refill vector is
+/// used, and [`refill_from_context()`] should be called instead.
+/// 
+/// # Safety
+/// Must only be called from the XTLB refill exception handler, with PageMask set to the page size
+/// used by the page table, and `page_table_base` pointing to a valid page table. Pointers are 32
+/// bits wide, so the address of the entry is truncated to 32 bits.
+#[inline(always)]
+pub unsafe fn xrefill_from_xcontext(page_table_base: u64) {
+    // Bits 4..=32 hold R and BadVPN2, already in their final positions
+    let offset = xcontext().0 & 0x1_FFFF_FFF0;
+    refill_from_ptes(page_table_base.wrapping_add(offset) as usize as *const [u64; 2]);
+}
+
+/// Searches the TLB for an entry mapping the provided virtual address and address space ID,
+/// returning its index if found.
+/// 
+/// `vaddr` must be the 64-bit (sign-extended, if using 32-bit addressing) form of the address.
+/// 
+/// The EntryHi register is used for the search, but its original value is restored before returning.
+/// The Index register is overwritten with the result of the probe.
+/// 
+/// # Safety
+/// If an interrupt occurs during this function, and the handler relies on EntryHi or Index, it will
+/// observe the temporary values used for the probe.
+#[must_use]
+#[inline(always)]
+pub unsafe fn find_tlb_entry(vaddr: u64, asid: u8) -> Option<u8> {
+    let saved = entryhi();
+    
+    set_entryhi(EntryHiReg((vaddr & 0xC00000FF_FFFFE000) | (asid as u64)));
+    tlb_probe();
+    let result = index();
+    
+    set_entryhi(saved);
+    
+    if result.probe() {
+        None
+    } else {
+        Some(result.index())
+    }
+}
+
+/// Locks the lowest `count` TLB entries, by setting the Wired register, and initializes each locked
+/// entry so that it can never match an address.
+/// 
+/// After a reset, the Random register starts at 31 and decrements down to the value of Wired before
+/// wrapping, so `tlbwr` will only ever target indices `>= count` after calling this function. The
+/// locked entries can then be filled in using `tlbwi`.
+/// 
+/// Each locked entry is given a distinct VPN2 within KSEG0 (which is never translated through the
+/// TLB), and invalid EntryLo values. `count` is limited to 32, the number of TLB entries.
+/// 
+/// Returns the range of locked indices.
+/// 
+/// # Safety
+/// This overwrites the Index, EntryHi, EntryLo0, EntryLo1, and PageMask registers, along with the
+/// contents of the locked TLB entries.
+pub unsafe fn lock_tlb_entries(count: u8) -> Range<u8> {
+    let count = count.min(32);
+    
+    set_entrylo0(EntryLoReg(0));
+    set_entrylo1(EntryLoReg(0));
+    set_pagemask(PageMaskReg(0));
+    for i in 0..count {
+        set_entryhi(EntryHiReg(0xFFFFFFFF_80000000 | ((i as u64) << 13)));
+        set_index(IndexReg(0).with_index(i));
+        tlb_write_indexed();
+    }
+    
+    set_wired(WiredReg(0).with_wired(count));
+    
+    0..count
+}
+
+/// Returns an iterator which reads each of the 32 TLB entries, in index order.
+/// 
+/// Each call to [`Iterator::next()`] reads one entry using `tlbr`, which overwrites EntryHi,
+/// EntryLo0, EntryLo1, and PageMask. The iterator saves these registers, along with Index, when
+/// created, and restores them once it's exhausted or dropped.
+/// 
+/// ```no_run
+/// # use n64_pac::cp0;
+/// let free = unsafe { cp0::tlb_entries() }.position(|entry| !entry.entry_lo0.valid() && !entry.entry_lo1.valid());
+/// ```
+/// 
+/// # Safety
+/// The CP0 registers listed above hold temporary values while the iterator exists. An interrupt
+/// handler which relies on them will observe those values, and any changes made to them by other
+/// code during iteration will be lost when they're restored.
+pub unsafe fn tlb_entries() -> TlbEntries {
+    TlbEntries {
+        next: 0,
+        index: index(),
+        entry_hi: entryhi(),
+        entry_lo0: entrylo0(),
+        entry_lo1: entrylo1(),
+        page_mask: pagemask(),
+    }
+}
+
+/// Reads the TLB entry at `index` (0 to 31).
+/// 
+/// # Safety
+/// Overwrites the Index, EntryHi, EntryLo0, EntryLo1, and PageMask registers.
+#[inline(always)]
+pub unsafe fn read_tlb_entry(index: u8) -> TlbEntry {
+    set_index(IndexReg(0).with_index(index));
+    tlb_read();
+    
+    TlbEntry {
+        page_mask: pagemask(),
+        entry_hi: entryhi(),
+        entry_lo0: entrylo0(),
+        entry_lo1: entrylo1(),
+    }
+}
+
+/// Reads the CP0 registers which describe the most recent exception.
+/// 
+/// This should be called as early as possible in an exception handler, before any code which might
+/// trigger another exception (such as a TLB miss) overwrites these registers.
+#[must_use]
+#[inline(always)]
+pub fn exception_info() -> ExceptionInfo {
+    ExceptionInfo {
+        cause: cause(),
+        status: status(),
+        epc: exception_pc(),
+        badvaddr: badvaddr(),
+        context: context(),
+        entry_hi: entryhi(),
+    }
+}
+
+/// Reads the CacheErr and ErrorEPC registers which describe the most recent cache error exception.
+/// 
+/// Cache error exceptions use a dedicated vector at `0xA0000100` (or `0xBFC00300` when Status.BEV is
+/// set). It's in uncached KSEG1, as the cache itself can't be trusted, and the return address is
+/// saved to ErrorEPC rather than EPC. Status.ERL is set while the handler runs.
+/// 
+/// The VR4300 doesn't check cache parity, so on retail hardware this exception never occurs and
+/// CacheErr always reads as zero. See [`CacheErrReg`].
+#[must_use]
+#[inline(always)]
+pub fn cache_error_info() -> CacheError {
+    CacheError::new(cache_err(), error_exception_pc())
+}
+
+/// Returns the coprocessor unit number which caused the most recent Coprocessor Unusable exception.
+/// 
+/// Only meaningful while handling an exception whose code is [`ExceptionCode::CoprocessorUnusable`];
+/// the value is undefined otherwise. A value of `1` means the FPU was accessed while disabled.
+#[must_use]
+#[inline(always)]
+pub fn on_coprocessor_unusable() -> u8 {
+    cause().ce()
+}
+
+/// Returns true if the reset button has been pressed, and the console is about to reset.
+/// 
+/// When the reset button is pressed, the PIF asserts the CPU's PRE_NMI line (`/INT2`, Cause.IP4),
+/// then roughly 0.5 seconds later triggers the NMI, which restarts the CPU at the reset vector.
+/// The line stays asserted until the NMI, so this can be polled at any time during that window,
+/// whether or not the interrupt is unmasked. Games use the window to stop audio, and to finish
+/// writes to save memory.
+/// 
+/// The reset is committed once PRE_NMI is raised: releasing the button doesn't cancel it, so a tap
+/// and a held press can't be told apart. The PIF doesn't report the button's state through the SI,
+/// so this is the only way to detect it.
+#[must_use]
+#[inline(always)]
+pub fn reset_pending() -> bool {
+    cause().ip4()
+}
+
+/// Returns whether the FPU (coprocessor 1) is currently usable, i.e. whether Status.CU1 is set.
+#[must_use]
+#[inline(always)]
+pub fn fpu_enabled() -> bool {
+    status().cu() & 0b10 != 0
+}
+
+/// Disables the FPU by clearing Status.CU1, so the next FPU instruction raises a Coprocessor
+/// Unusable exception.
+/// 
+/// This is the first half of lazy FPU context switching, where the FPU registers are only saved
+/// and restored for tasks which actually use them:
+/// 
+/// 1. When switching to a task, call `defer_fpu()` instead of restoring its FPU registers.
+/// 2. If the task executes an FPU instruction, a Coprocessor Unusable exception occurs with
+///    [`on_coprocessor_unusable()`] returning `1`.
+/// 3. In that exception handler, save the FPU registers into the context of the task which last
+///    used the FPU (if it isn't the current task), restore the current task's FPU registers, then
+///    call [`enable_fpu()`] and return to the faulting instruction (EPC is left unchanged).
+/// 
+/// Tasks which never touch the FPU never pay for saving or restoring its 32 registers and FCR31.
+/// 
+/// # Safety
+/// If the exception handler saves and restores the Status register, the restored value must have
+/// CU1 cleared or set accordingly, otherwise the change made by this function will be lost.
+#[inline(always)]
+pub unsafe fn defer_fpu() {
+    modify_status(|reg| reg.with_cu(reg.cu() & !0b10));
+}
+
+/// Enables the FPU by setting Status.CU1.
+/// 
+/// See [`defer_fpu()`] for how this fits into lazy FPU context switching.
+/// 
+/// # Safety
+/// If the exception handler saves and restores the Status register, the restored value must also
+/// have CU1 set, otherwise the FPU will be disabled again on return.
+#[inline(always)]
+pub unsafe fn enable_fpu() {
+    modify_status(|reg| reg.with_cu(reg.cu() | 0b10));
+}
+
+/// Runs `func` with interrupts disabled, then restores the previous interrupt enable state.
+/// 
+/// Only Status.IE is changed, so this can be nested, and can be used while interrupts are already
+/// disabled. Interrupts which become pending while disabled are taken once they're re-enabled.
+#[inline]
+pub fn interrupt_free<F: FnOnce() -> R, R>(func: F) -> R {
+    let status = status();
+    if status.ie() {
+        unsafe { set_status(status.with_ie(false)); }
+    }
+    
+    let result = func();
+    
+    if status.ie() {
+        unsafe { modify_status(|reg| reg.with_ie(true)); }
+    }
+    
+    result
+}
+
+/// [`critical_section::Impl`] for the N64, using the same Status.IE save/restore as
+/// [`interrupt_free()`]. The N64 has a single CPU core, so disabling interrupts is sufficient.
+#[cfg(feature = "critical-section")]
+struct CriticalSection;
+#[cfg(feature = "critical-section")]
+critical_section::set_impl!(CriticalSection);
+#[cfg(feature = "critical-section")]
+unsafe impl critical_section::Impl for CriticalSection {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        let status = status();
+        if status.ie() {
+            set_status(status.with_ie(false));
+        }
+        
+        status.ie()
+    }
+    
+    unsafe fn release(was_enabled: critical_section::RawRestoreState) {
+        if was_enabled {
+            modify_status(|reg| reg.with_ie(true));
+        }
+    }
+}
+
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct IndexReg(pub u32): Debug {
         pub index: u8 @ 0..=5,
         pub probe: bool @ 31,
     }
 }
 derive_tofrom_primitive!(IndexReg, u32);
+impl_pod!(IndexReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct RandomReg(pub u32): Debug {
         pub random: u8 [ro] @ 0..=5,
     }
 }
 derive_tofrom_primitive!(RandomReg, u32);
+impl_pod!(RandomReg);
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -143,6 +658,15 @@ pub enum CacheAlgorithm {
     #[default]
     Cached = 0b011,
 }
+impl fmt::Display for CacheAlgorithm {
+    /// Writes the cache behavior in words, such as `Cached (writeback)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Uncached => "Uncached",
+            Self::Cached => "Cached (writeback)",
+        })
+    }
+}
 
 bitfield! {
     /// Contains the page frame number and other configuration bits for a TLB entry.
@@ -150,7 +674,9 @@ bitfield! {
     /// EntryLo0 and EntryLo1 registers use the same format, thus this type can be used for both registers.
     /// 
     /// EntryLo0 is used for even virtual pages, EntryLo1 for odd virtual pages.
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct EntryLoReg(pub u32): Debug {
         pub global: bool @ 0,
         pub valid: bool @ 1,
@@ -160,9 +686,35 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(EntryLoReg, u32);
+impl_pod!(EntryLoReg);
+impl EntryLoReg {
+    /// Creates an entry mapping the 4KB aligned physical address `paddr`, with the given flags.
+    /// 
+    /// The page frame number is computed as `paddr >> 12`. Debug builds assert that `paddr` is aligned.
+    pub fn for_page(paddr: u32, cache: CacheAlgorithm, dirty: bool, valid: bool, global: bool) -> Self {
+        debug_assert_eq!(paddr & 0xFFF, 0, "physical address must be 4KB aligned");
+        
+        Self(0)
+            .with_page_frame_number(paddr >> 12)
+            .with_cache_algorithm(cache)
+            .with_dirty(dirty)
+            .with_valid(valid)
+            .with_global(global)
+    }
+    
+    /// Returns the physical address of the start of the mapped page (`page_frame_number << 12`).
+    /// 
+    /// Only the low 32 bits of the address are returned, as the RCP can't decode anything higher.
+    #[must_use]
+    pub fn physical_address(&self) -> u32 {
+        self.page_frame_number() << 12
+    }
+}
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ContextReg(pub u64): Debug {
         /// Page number of virtual address whose translation is invalid, divided by 2
         pub bad_vpn2: u32 @ 4..=22,
@@ -175,6 +727,7 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ContextReg, u64);
+impl_pod!(ContextReg);
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u16)]
@@ -189,25 +742,55 @@ pub enum PageSize {
     #[default]
     Undefined,
 }
+impl PageSize {
+    /// Returns the size of a single page in bytes, or `None` for [`PageSize::Undefined`].
+    /// 
+    /// Each TLB entry maps a pair of pages, covering twice this size.
+    #[must_use]
+    pub fn bytes(self) -> Option<u32> {
+        match self {
+            Self::Undefined => None,
+            size => Some((u16::from(size) as u32 + 1) << 12),
+        }
+    }
+}
+impl fmt::Display for PageSize {
+    /// Writes the size of a single page, such as `4 KiB` or `16 MiB`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bytes() {
+            Some(bytes) if bytes >= 0x100000 => write!(f, "{} MiB", bytes >> 20),
+            Some(bytes) => write!(f, "{} KiB", bytes >> 10),
+            None => f.write_str("Undefined"),
+        }
+    }
+}
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct PageMaskReg(pub u32): Debug {
         pub mask: u16 [PageSize] @ 13..=24,
     }
 }
 derive_tofrom_primitive!(PageMaskReg, u32);
+impl_pod!(PageMaskReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct WiredReg(pub u32): Debug {
         pub wired: u8 @ 0..=5,
     }
 }
 derive_tofrom_primitive!(WiredReg, u32);
+impl_pod!(WiredReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct BadVAddrReg(pub u64): Debug {
         /// Most recently translated vitual address that had an invalid translation or an addressing error (32-bit mode)
         pub badvaddr_u32: u32 [ro] @ 0..=31,
@@ -217,6 +800,7 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(BadVAddrReg, u64);
+impl_pod!(BadVAddrReg);
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -229,7 +813,9 @@ pub enum VAddrRegion {
 }
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct EntryHiReg(pub u64): Debug {
         /// Address space identifier
         pub asid: u8 @ 0..=7,
@@ -248,9 +834,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(EntryHiReg, u64);
+impl_pod!(EntryHiReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct StatusReg(pub u32): Debug {
         /// Global Interrupt Enable
         /// - 0 = Disabled
@@ -320,6 +909,38 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(StatusReg, u32);
+impl_pod!(StatusReg);
+impl StatusReg {
+    /// Status for a game which uses floating-point: the FPU is usable (CU1), with all 32
+    /// floating-point registers available as 64-bit registers (FR).
+    /// 
+    /// Kernel mode with 32-bit addressing, and both exception levels cleared. The RCP (IP2) and
+    /// pre-NMI (IP4) interrupts are unmasked, but interrupts are left globally disabled (IE), so
+    /// they can be enabled once handlers have been installed. The timer interrupt stays masked; see
+    /// [`timer`] for enabling it.
+    /// 
+    /// Code built for the `mips-nintendo64-none` target is compatible with either FR setting
+    /// (`+fpxx`), so FR only matters to hand-written assembly, or code built with another ABI. With
+    /// FR set, exception handlers must save all 32 floating-point registers as doublewords.
+    pub fn fpu_game() -> Self {
+        Self::integer_only()
+            .with_fr(true)
+            .with_cu(0b0010)
+    }
+    
+    /// Status for code which never uses floating-point: like [`fpu_game()`][Self::fpu_game()], but
+    /// with the FPU unusable.
+    /// 
+    /// Any FPU instruction, including ones generated by the compiler for a stray `f32`, raises a
+    /// coprocessor unusable exception, making accidental FPU use easy to find. Exception handlers
+    /// also don't need to save the floating-point registers. See [`defer_fpu()`] for enabling the
+    /// FPU lazily instead.
+    pub fn integer_only() -> Self {
+        Self(0)
+            .with_im_int0(true)
+            .with_im_int2(true)
+    }
+}
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -346,9 +967,36 @@ pub enum ExceptionCode {
     #[default]
     Reserved,
 }
+impl ExceptionCode {
+    /// Returns a short, human readable, description of the exception.
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Interrupt => "Interrupt",
+            Self::TlbModification => "TLB modification",
+            Self::TlbMissOnLoad => "TLB miss on load",
+            Self::TlbMissOnStore => "TLB miss on store",
+            Self::AddressErrorOnLoad => "Address error on load",
+            Self::AddressErrorOnStore => "Address error on store",
+            Self::InstructionBusError => "Instruction bus error",
+            Self::DataBusError => "Data bus error",
+            Self::Syscall => "Syscall",
+            Self::Breakpoint => "Breakpoint",
+            Self::ReservedInstruction => "Reserved instruction",
+            Self::CoprocessorUnusable => "Coprocessor unusable",
+            Self::ArithmeticOverflow => "Arithmetic overflow",
+            Self::Trap => "Trap",
+            Self::FloatingPoint => "Floating point",
+            Self::Watch => "Watch",
+            Self::Reserved => "Reserved exception",
+        }
+    }
+}
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct CauseReg(pub u32): Debug {
         pub exception_code: u8 [ExceptionCode, ro] @ 2..=6,
         
@@ -381,9 +1029,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(CauseReg, u32);
+impl_pod!(CauseReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ExceptionPcReg(pub u64): Debug {
         /// The 32-bit address at which processing resumes after an exception/interrupt has been serviced. (32-bit mode)
         pub epc_u32: u32 @ 0..=31,
@@ -393,9 +1044,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ExceptionPcReg, u64);
+impl_pod!(ExceptionPcReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ProcessorRevisionIdReg(pub u32): Debug {
         /// Processor revision number
         pub revision: u8 [ro] @ 0..=7,
@@ -404,9 +1058,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ProcessorRevisionIdReg, u32);
+impl_pod!(ProcessorRevisionIdReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ConfigReg(pub u32): Debug {
         /// Coherency algorithm for kernel segment 0 (kseg0)
         pub k0: u8 [CacheAlgorithm] @ 0..=2,
@@ -418,6 +1075,8 @@ bitfield! {
         /// 
         /// - 0 = little endian
         /// - 1 = big endian (default on cold reset)
+        /// 
+        /// [`set_config()`] never changes this bit. See [`set_endianness()`].
         pub be: bool @ 15,
         
         /// Sets writeback data pattern for the SysAD bus
@@ -425,6 +1084,8 @@ bitfield! {
         /// - 0 = D (default on cold reset)
         /// - 6 = DxxDxx (2 doublewords / 6 cycles)
         /// - Others = Reserved/Unknown
+        /// 
+        /// See [`ConfigReg::writeback_pattern()`] for a typed accessor.
         pub ep: u8 @ 24..=27,
         
         /// Operating frequency ratio
@@ -434,9 +1095,100 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ConfigReg, u32);
+impl_pod!(ConfigReg);
+impl ConfigReg {
+    /// Decodes the [`ec`][Self::ec()] field into the CPU's operating frequency ratio.
+    #[must_use]
+    pub fn freq_ratio(&self) -> FreqRatio {
+        match self.ec() {
+            0b110 => FreqRatio::OneToOne,
+            0b111 => FreqRatio::ThreeToTwo,
+            0b000 => FreqRatio::TwoToOne,
+            0b001 => FreqRatio::ThreeToOne,
+            other => FreqRatio::Reserved(other),
+        }
+    }
+    
+    /// Decodes the [`ep`][Self::ep()] field, returning `None` for a reserved pattern.
+    #[must_use]
+    pub fn writeback_pattern(&self) -> Option<WritebackPattern> {
+        match self.ep() {
+            0 => Some(WritebackPattern::D),
+            6 => Some(WritebackPattern::DxxDxx),
+            _ => None,
+        }
+    }
+    
+    /// Sets the [`ep`][Self::ep()] field to a documented writeback pattern.
+    pub fn with_writeback_pattern(self, pattern: WritebackPattern) -> Self {
+        self.with_ep(pattern as u8)
+    }
+}
+
+/// Frequency of the MasterClock input to the CPU, supplied by the RCP.
+pub const MASTER_CLOCK_HZ: u32 = 62_500_000;
+
+/// Data pattern used when writing back a data cache line over the SysAD bus, selected by the `ep`
+/// field of [`ConfigReg`].
+/// 
+/// The N64 runs with [`WritebackPattern::D`], the cold reset default, which the RCP accepts at full
+/// rate. The slower pattern only exists for external agents which can't accept a doubleword every
+/// cycle; there's no known reason to select it on an N64, and it only slows down cache writebacks.
+/// Reserved `ep` values leave the bus timing undefined, so they aren't representable here.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum WritebackPattern {
+    /// One doubleword every cycle (`ep` = 0).
+    D = 0,
+    /// Two doublewords every six cycles (`ep` = 6).
+    DxxDxx = 6,
+}
+
+/// Ratio of the CPU pipeline clock (PClock) to the MasterClock, as set by the DivMode pins.
+/// 
+/// See [`ConfigReg::freq_ratio()`]. Retail consoles use [`FreqRatio::ThreeToTwo`], for a 93.75 MHz
+/// PClock.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FreqRatio {
+    /// PClock = MasterClock
+    OneToOne,
+    /// PClock = MasterClock * 1.5
+    ThreeToTwo,
+    /// PClock = MasterClock * 2
+    TwoToOne,
+    /// PClock = MasterClock * 3
+    ThreeToOne,
+    /// Undocumented `ec` value.
+    Reserved(u8),
+}
+impl FreqRatio {
+    /// Returns the PClock frequency for the given MasterClock frequency, or `None` if the ratio is
+    /// [`Reserved`][FreqRatio::Reserved].
+    #[must_use]
+    pub fn pclock_hz(self, master_clock_hz: u32) -> Option<u32> {
+        match self {
+            Self::OneToOne => Some(master_clock_hz),
+            Self::ThreeToTwo => Some(master_clock_hz / 2 * 3),
+            Self::TwoToOne => Some(master_clock_hz * 2),
+            Self::ThreeToOne => Some(master_clock_hz * 3),
+            Self::Reserved(_) => None,
+        }
+    }
+}
+
+/// Returns the CPU clock frequency (PClock), computed from the frequency ratio reported by the
+/// Config register, and the N64's [`MASTER_CLOCK_HZ`].
+/// 
+/// The Count register increments at half this rate. Returns `None` if the ratio is reserved.
+#[must_use]
+pub fn cpu_clock_hz() -> Option<u32> {
+    config().freq_ratio().pclock_hz(MASTER_CLOCK_HZ)
+}
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct WatchLoReg(pub u32): Debug {
         /// If true, trigger an exception when a store instruction is executed.
         pub w: bool @ 0,
@@ -450,9 +1202,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(WatchLoReg, u32);
+impl_pod!(WatchLoReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct WatchHiReg(pub u32): Debug {
         /// Bits \[35:32\] of the physical address to watch for.
         /// 
@@ -464,9 +1219,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(WatchHiReg, u32);
+impl_pod!(WatchHiReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct XContextReg(pub u64): Debug {
         pub badvpn2: u32 @ 4..=30,
         pub region: u8 [VAddrRegion] @ 31..=32,
@@ -474,17 +1232,124 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(XContextReg, u64);
+impl_pod!(XContextReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ParityErrorReg(pub u32): Debug {
         pub diagnostic: u8 @ 0..=7,
     }
 }
 derive_tofrom_primitive!(ParityErrorReg, u32);
+impl_pod!(ParityErrorReg);
+
+bitfield! {
+    /// Describes the source of a cache error exception.
+    /// 
+    /// The layout is shared with the R4000 family. The VR4300 has no cache parity or ECC checking,
+    /// so this register is unused and always reads as zero; it's provided for completeness.
+    #[must_use]
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct CacheErrReg(pub u32): Debug {
+        /// Physical address bits \[14:12\] of the primary cache line in error
+        pub pidx: u8 [ro] @ 0..=2,
+        
+        /// Physical address bits \[21:3\] of the secondary cache line in error
+        pub sidx: u32 [ro] @ 3..=21,
+        
+        /// Error occurred on a store miss
+        pub ei: bool [ro] @ 24,
+        
+        /// Error occurred in both the instruction and data caches
+        pub eb: bool [ro] @ 25,
+        
+        /// Error occurred on the SysAD bus (external)
+        pub ee: bool [ro] @ 26,
+        
+        /// Error occurred while servicing an external request
+        pub es: bool [ro] @ 27,
+        
+        /// Error occurred in the tag field
+        pub et: bool [ro] @ 28,
+        
+        /// Error occurred in the data field
+        pub ed: bool [ro] @ 29,
+        
+        /// Error level (0 = primary cache, 1 = secondary cache)
+        pub ec: bool [ro] @ 30,
+        
+        /// Type of reference (0 = instruction, 1 = data)
+        pub er: bool [ro] @ 31,
+    }
+}
+derive_tofrom_primitive!(CacheErrReg, u32);
+impl_pod!(CacheErrReg);
+
+/// Which cache reference encountered a cache error.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CacheErrorReference {
+    Instruction,
+    Data,
+}
+
+/// Which cache level encountered a cache error.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CacheErrorLevel {
+    Primary,
+    Secondary,
+}
+
+/// Decoded view of a cache error exception.
+/// 
+/// See [`cache_error_info()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CacheError {
+    /// Raw CacheErr register value.
+    pub raw: CacheErrReg,
+    /// Address of the instruction which encountered the error.
+    pub error_epc: ErrorExceptionPcReg,
+    /// Whether an instruction fetch or data access encountered the error.
+    pub reference: CacheErrorReference,
+    /// Which cache level encountered the error.
+    pub level: CacheErrorLevel,
+    /// The error was in the cache line's data.
+    pub data_error: bool,
+    /// The error was in the cache line's tag.
+    pub tag_error: bool,
+    /// The error occurred on the SysAD bus, rather than inside the cache.
+    pub external: bool,
+    /// The error occurred while servicing an external request.
+    pub external_request: bool,
+    /// The error also occurred in the other primary cache.
+    pub both_caches: bool,
+    /// The error occurred on a store miss.
+    pub store_miss: bool,
+}
+impl CacheError {
+    /// Decodes a raw CacheErr value, along with the ErrorEPC of the exception.
+    pub fn new(raw: CacheErrReg, error_epc: ErrorExceptionPcReg) -> Self {
+        Self {
+            raw,
+            error_epc,
+            reference: if raw.er() { CacheErrorReference::Data } else { CacheErrorReference::Instruction },
+            level: if raw.ec() { CacheErrorLevel::Secondary } else { CacheErrorLevel::Primary },
+            data_error: raw.ed(),
+            tag_error: raw.et(),
+            external: raw.ee(),
+            external_request: raw.es(),
+            both_caches: raw.eb(),
+            store_miss: raw.ei(),
+        }
+    }
+}
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct TagLoReg(pub u32): Debug {
         /// Specifies the primary cache state
         /// 
@@ -504,9 +1369,12 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(TagLoReg, u32);
+impl_pod!(TagLoReg);
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ErrorExceptionPcReg(pub u64): Debug {
         /// The 32-bit program counter address on cold reset, soft reset, or NMI exception. (32-bit mode)
         pub epc_u32: u32 @ 0..=31,
@@ -516,11 +1384,322 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ErrorExceptionPcReg, u64);
+impl_pod!(ErrorExceptionPcReg);
+
+/// Snapshot of the CP0 registers which describe an exception.
+/// 
+/// See [`exception_info()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ExceptionInfo {
+    pub cause: CauseReg,
+    pub status: StatusReg,
+    pub epc: ExceptionPcReg,
+    pub badvaddr: BadVAddrReg,
+    pub context: ContextReg,
+    /// Holds the ASID (and for TLB exceptions, the VPN2 of BadVAddr) at the time of the exception.
+    pub entry_hi: EntryHiReg,
+}
+impl ExceptionInfo {
+    /// Returns `true` if this is a TLB exception (modification, or miss on load/store).
+    #[must_use]
+    pub fn is_tlb_exception(&self) -> bool {
+        matches!(
+            self.cause.exception_code(),
+            ExceptionCode::TlbModification | ExceptionCode::TlbMissOnLoad | ExceptionCode::TlbMissOnStore
+        )
+    }
+    
+    /// Checks that BadVAddr and Context.BadVPN2 describe the same faulting page.
+    /// 
+    /// Both registers are written by a TLB exception, with BadVPN2 holding bits 13 to 31 of BadVAddr.
+    /// If they disagree, one of them was overwritten before [`exception_info()`] was called; usually
+    /// because the handler accessed a TLB mapped address, causing a nested TLB exception.
+    /// 
+    /// Context is only written by TLB exceptions, so `true` is always returned for other exceptions.
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        !self.is_tlb_exception() || self.context.bad_vpn2() == ((self.badvaddr.0 >> 13) & 0x7FFFF) as u32
+    }
+}
+impl fmt::Display for ExceptionInfo {
+    /// Writes a one-line summary of the exception, such as
+    /// `TLB miss on load @ EPC=0x80001234, bad=0xDEAD0000, asid=3, in delay slot`.
+    /// 
+    /// BadVAddr and the ASID are only included for exceptions which set them. Addresses are printed
+    /// as 32-bit values.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = self.cause.exception_code();
+        write!(f, "{} @ EPC={:#010X}", code.description(), self.epc.epc_u32())?;
+        
+        match code {
+            ExceptionCode::TlbModification | ExceptionCode::TlbMissOnLoad | ExceptionCode::TlbMissOnStore => {
+                write!(f, ", bad={:#010X}, asid={}", self.badvaddr.badvaddr_u32(), self.entry_hi.asid())?;
+            }
+            ExceptionCode::AddressErrorOnLoad | ExceptionCode::AddressErrorOnStore => {
+                write!(f, ", bad={:#010X}", self.badvaddr.badvaddr_u32())?;
+            }
+            ExceptionCode::CoprocessorUnusable => write!(f, ", cop={}", self.cause.ce())?,
+            ExceptionCode::Interrupt => write!(f, ", pending={:#04X}", (self.cause.0 >> 8) & 0xFF)?,
+            _ => {}
+        }
+        
+        if self.cause.branch_delay() {
+            f.write_str(", in delay slot")?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// The contents of a single TLB entry, as read by [`read_tlb_entry()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TlbEntry {
+    pub page_mask: PageMaskReg,
+    pub entry_hi: EntryHiReg,
+    /// Mapping of the even page
+    pub entry_lo0: EntryLoReg,
+    /// Mapping of the odd page
+    pub entry_lo1: EntryLoReg,
+}
+
+/// Iterator over all TLB entries, created by [`tlb_entries()`].
+/// 
+/// The CP0 registers used to read each entry are restored when the iterator is exhausted or dropped.
+pub struct TlbEntries {
+    next: u8,
+    index: IndexReg,
+    entry_hi: EntryHiReg,
+    entry_lo0: EntryLoReg,
+    entry_lo1: EntryLoReg,
+    page_mask: PageMaskReg,
+}
+impl TlbEntries {
+    fn restore(&self) {
+        unsafe {
+            set_index(self.index);
+            set_entryhi(self.entry_hi);
+            set_entrylo0(self.entry_lo0);
+            set_entrylo1(self.entry_lo1);
+            set_pagemask(self.page_mask);
+        }
+    }
+}
+impl Iterator for TlbEntries {
+    type Item = TlbEntry;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= 32 {
+            return None;
+        }
+        
+        let entry = unsafe { read_tlb_entry(self.next) };
+        self.next += 1;
+        if self.next == 32 {
+            self.restore();
+        }
+        
+        Some(entry)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = 32 - self.next as usize;
+        (remaining, Some(remaining))
+    }
+}
+impl ExactSizeIterator for TlbEntries {}
+impl Drop for TlbEntries {
+    fn drop(&mut self) {
+        // Already restored if the iterator was exhausted
+        if self.next != 0 && self.next < 32 {
+            self.restore();
+        }
+    }
+}
+
+/// Every CP0 register supported by this crate, for tools which need to enumerate or access registers
+/// generically, such as a register browser or GDB stub.
+/// 
+/// The typed functions (such as [`status()`]) should be preferred when the register is known.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Cp0Register {
+    Index = 0,
+    Random = 1,
+    EntryLo0 = 2,
+    EntryLo1 = 3,
+    Context = 4,
+    PageMask = 5,
+    Wired = 6,
+    BadVAddr = 8,
+    Count = 9,
+    EntryHi = 10,
+    Compare = 11,
+    Status = 12,
+    Cause = 13,
+    Epc = 14,
+    PRId = 15,
+    Config = 16,
+    LLAddr = 17,
+    WatchLo = 18,
+    WatchHi = 19,
+    XContext = 20,
+    ParityError = 26,
+    CacheErr = 27,
+    TagLo = 28,
+    ErrorEpc = 30,
+}
+impl Cp0Register {
+    /// All registers, in index order.
+    pub const ALL: [Cp0Register; 24] = [
+        Self::Index,
+        Self::Random,
+        Self::EntryLo0,
+        Self::EntryLo1,
+        Self::Context,
+        Self::PageMask,
+        Self::Wired,
+        Self::BadVAddr,
+        Self::Count,
+        Self::EntryHi,
+        Self::Compare,
+        Self::Status,
+        Self::Cause,
+        Self::Epc,
+        Self::PRId,
+        Self::Config,
+        Self::LLAddr,
+        Self::WatchLo,
+        Self::WatchHi,
+        Self::XContext,
+        Self::ParityError,
+        Self::CacheErr,
+        Self::TagLo,
+        Self::ErrorEpc,
+    ];
+    
+    /// Returns an iterator over all registers, in index order.
+    pub fn all() -> impl Iterator<Item = Cp0Register> {
+        Self::ALL.into_iter()
+    }
+    
+    /// Returns the register's name, as used by the VR4300 manual.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Index => "Index",
+            Self::Random => "Random",
+            Self::EntryLo0 => "EntryLo0",
+            Self::EntryLo1 => "EntryLo1",
+            Self::Context => "Context",
+            Self::PageMask => "PageMask",
+            Self::Wired => "Wired",
+            Self::BadVAddr => "BadVAddr",
+            Self::Count => "Count",
+            Self::EntryHi => "EntryHi",
+            Self::Compare => "Compare",
+            Self::Status => "Status",
+            Self::Cause => "Cause",
+            Self::Epc => "Epc",
+            Self::PRId => "PRId",
+            Self::Config => "Config",
+            Self::LLAddr => "LLAddr",
+            Self::WatchLo => "WatchLo",
+            Self::WatchHi => "WatchHi",
+            Self::XContext => "XContext",
+            Self::ParityError => "ParityError",
+            Self::CacheErr => "CacheErr",
+            Self::TagLo => "TagLo",
+            Self::ErrorEpc => "ErrorEpc",
+        }
+    }
+    
+    /// Returns the register's index, as used by the `mfc0`/`mtc0` instructions.
+    #[must_use]
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+    
+    /// Returns `true` if the register can be written. BadVAddr, PRId, and CacheErr are read-only.
+    #[must_use]
+    pub fn is_writable(self) -> bool {
+        !matches!(self, Self::BadVAddr | Self::PRId | Self::CacheErr)
+    }
+    
+    /// Returns `true` if the register is 64 bits wide.
+    #[must_use]
+    pub fn is_64bit(self) -> bool {
+        matches!(self, Self::Context | Self::BadVAddr | Self::EntryHi | Self::Epc | Self::XContext | Self::ErrorEpc)
+    }
+    
+    /// Reads the register's raw value, zero-extending 32-bit registers.
+    #[must_use]
+    pub fn read(self) -> u64 {
+        match self {
+            Self::Index => read_u32::<0>() as u64,
+            Self::Random => read_u32::<1>() as u64,
+            Self::EntryLo0 => read_u32::<2>() as u64,
+            Self::EntryLo1 => read_u32::<3>() as u64,
+            Self::Context => read_u64::<4>(),
+            Self::PageMask => read_u32::<5>() as u64,
+            Self::Wired => read_u32::<6>() as u64,
+            Self::BadVAddr => read_u64::<8>(),
+            Self::Count => read_u32::<9>() as u64,
+            Self::EntryHi => read_u64::<10>(),
+            Self::Compare => read_u32::<11>() as u64,
+            Self::Status => read_u32::<12>() as u64,
+            Self::Cause => read_u32::<13>() as u64,
+            Self::Epc => read_u64::<14>(),
+            Self::PRId => read_u32::<15>() as u64,
+            Self::Config => read_u32::<16>() as u64,
+            Self::LLAddr => read_u32::<17>() as u64,
+            Self::WatchLo => read_u32::<18>() as u64,
+            Self::WatchHi => read_u32::<19>() as u64,
+            Self::XContext => read_u64::<20>(),
+            Self::ParityError => read_u32::<26>() as u64,
+            Self::CacheErr => read_u32::<27>() as u64,
+            Self::TagLo => read_u32::<28>() as u64,
+            Self::ErrorEpc => read_u64::<30>(),
+        }
+    }
+    
+    /// Writes a raw value to the register, truncating it for 32-bit registers. Writes to read-only
+    /// registers are ignored, and Config's endianness bit is preserved (see [`set_config()`]).
+    /// 
+    /// # Safety
+    /// See the `set_*` function of the register being written.
+    pub unsafe fn write(self, value: u64) {
+        match self {
+            Self::Index => write_u32::<0>(value as u32),
+            Self::Random => write_u32::<1>(value as u32),
+            Self::EntryLo0 => write_u32::<2>(value as u32),
+            Self::EntryLo1 => write_u32::<3>(value as u32),
+            Self::Context => write_u64::<4>(value),
+            Self::PageMask => write_u32::<5>(value as u32),
+            Self::Wired => write_u32::<6>(value as u32),
+            Self::Count => write_u32::<9>(value as u32),
+            Self::EntryHi => write_u64::<10>(value),
+            Self::Compare => write_u32::<11>(value as u32),
+            Self::Status => write_u32::<12>(value as u32),
+            Self::Cause => write_u32::<13>(value as u32),
+            Self::Epc => write_u64::<14>(value),
+            Self::Config => set_config(ConfigReg(value as u32)),
+            Self::LLAddr => write_u32::<17>(value as u32),
+            Self::WatchLo => write_u32::<18>(value as u32),
+            Self::WatchHi => write_u32::<19>(value as u32),
+            Self::XContext => write_u64::<20>(value),
+            Self::ParityError => write_u32::<26>(value as u32),
+            Self::TagLo => write_u32::<28>(value as u32),
+            Self::ErrorEpc => write_u64::<30>(value),
+            Self::BadVAddr | Self::PRId | Self::CacheErr => {}
+        }
+    }
+}
 
 
 #[inline(always)]
 pub fn read_u32<const INDEX: u32>() -> u32 {
     let value: u32;
+    #[cfg(target_arch = "mips")]
     unsafe {
         asm!("
             .set noat
@@ -530,6 +1709,10 @@ pub fn read_u32<const INDEX: u32>() -> u32 {
         cp_reg = const INDEX
         );
     }
+    #[cfg(not(target_arch = "mips"))]
+    {
+        value = crate::host_unsupported(());
+    }
     
     value
 }
@@ -538,6 +1721,7 @@ pub fn read_u32<const INDEX: u32>() -> u32 {
 pub fn read_u64<const INDEX: u32>() -> u64 {
     let value_lo: u32;
     let value_hi: u32;
+    #[cfg(target_arch = "mips")]
     unsafe {
         asm!("
             .set noat
@@ -551,12 +1735,17 @@ pub fn read_u64<const INDEX: u32>() -> u64 {
         cp_reg = const INDEX
         );
     }
+    #[cfg(not(target_arch = "mips"))]
+    {
+        (value_lo, value_hi) = crate::host_unsupported(());
+    }
     
     ((value_hi as u64) << 32) | (value_lo as u64)
 }
 
 #[inline(always)]
 pub unsafe fn write_u32<const INDEX: u32>(value: u32) {
+    #[cfg(target_arch = "mips")]
     asm!("
         .set noat
         mtc0 {gpr}, ${cp_reg}
@@ -565,10 +1754,13 @@ pub unsafe fn write_u32<const INDEX: u32>(value: u32) {
     gpr = in(reg) value,
     cp_reg = const INDEX
     );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
 }
 
 #[inline(always)]
 pub unsafe fn write_u64<const INDEX: u32>(value: u64) {
+    #[cfg(target_arch = "mips")]
     asm!("
         .set noat
         dsll32 {tmp}, {hi}, 0
@@ -584,4 +1776,6 @@ pub unsafe fn write_u64<const INDEX: u32>(value: u64) {
     hi = in(reg) ((value >> 32) as u32),
     cp_reg = const INDEX
     );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
 }
\ No newline at end of file
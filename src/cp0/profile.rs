@@ -0,0 +1,112 @@
+//! Lightweight profiling built on the Count register.
+//! 
+//! A [`Scope`] records the Count register when created, and when dropped, adds the elapsed cycles
+//! to a fixed-size table keyed by the scope's name. No allocation is performed. The accumulated
+//! totals can be retrieved with [`report()`].
+//! 
+//! The Count register increments at half the CPU clock rate (46.875 MHz), so one cycle is roughly
+//! 21.3ns. Scopes shorter than one full Count period (roughly 91 seconds) are measured correctly,
+//! even if Count wraps around during the scope.
+//! 
+//! For one-off measurements, [`measure_cycles!`][crate::measure_cycles] returns the cycles taken by
+//! a single expression, without recording anything.
+
+use core::ptr::{addr_of, addr_of_mut};
+use crate::cp0::count;
+
+/// Maximum number of distinct scope names which can be tracked.
+pub const MAX_ENTRIES: usize = 32;
+
+static mut ENTRIES: [(&str, u64); MAX_ENTRIES] = [("", 0); MAX_ENTRIES];
+static mut ENTRY_COUNT: usize = 0;
+
+/// Measures the Count cycles between its creation and drop, accumulating them under its name.
+/// 
+/// ```no_run
+/// # use n64_pac::cp0::profile::Scope;
+/// fn update() {
+///     let _scope = unsafe { Scope::new("update") };
+///     // ...
+/// }
+/// ```
+#[must_use = "the scope measures until it is dropped"]
+pub struct Scope {
+    name: &'static str,
+    start: u32,
+}
+impl Scope {
+    /// Starts measuring a new scope.
+    /// 
+    /// Scopes with the same name share a single entry in the table. Once [`MAX_ENTRIES`] different
+    /// names have been recorded, scopes with any new name are ignored.
+    /// 
+    /// # Safety
+    /// Dropping the scope updates a global table without synchronization. Scopes must not be dropped
+    /// from an interrupt handler, while a scope could be dropped by the interrupted code.
+    #[inline(always)]
+    pub unsafe fn new(name: &'static str) -> Self {
+        Self { name, start: count() }
+    }
+}
+impl Drop for Scope {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let elapsed = count().wrapping_sub(self.start) as u64;
+        unsafe { record(self.name, elapsed); }
+    }
+}
+
+/// Evaluates an expression, returning its result and the number of Count cycles it took.
+/// 
+/// ```no_run
+/// # fn expensive_fn() -> u32 { 0 }
+/// let (sum, cycles) = n64_pac::measure_cycles!(expensive_fn());
+/// ```
+/// 
+/// Count is read immediately before and after evaluating the expression, and the difference is
+/// computed with wrapping subtraction, so measurements shorter than one Count period (roughly 91
+/// seconds) are correct even if Count wraps around. The expression is evaluated in place, not in a
+/// closure, so `?` and `return` behave as they would outside the macro.
+/// 
+/// The result includes the overhead of one of the two Count reads, a few cycles at most, so
+/// very short expressions are best measured in a loop. Count increments once every two CPU cycles.
+#[macro_export]
+macro_rules! measure_cycles {
+    ($expr:expr) => {{
+        let start = $crate::cp0::count();
+        let result = $expr;
+        let end = $crate::cp0::count();
+        (result, end.wrapping_sub(start))
+    }};
+}
+
+/// Adds `cycles` to the entry for `name`, creating the entry if needed.
+unsafe fn record(name: &'static str, cycles: u64) {
+    let entries = &mut *addr_of_mut!(ENTRIES);
+    let len = &mut *addr_of_mut!(ENTRY_COUNT);
+    
+    if let Some(entry) = entries[..*len].iter_mut().find(|(entry, _)| *entry == name) {
+        entry.1 += cycles;
+    } else if *len < MAX_ENTRIES {
+        entries[*len] = (name, cycles);
+        *len += 1;
+    }
+}
+
+/// Returns the accumulated Count cycles of every scope name, in the order each was first recorded.
+/// 
+/// # Safety
+/// No [`Scope`] may be dropped while the returned slice is in use.
+#[must_use]
+pub unsafe fn report() -> &'static [(&'static str, u64)] {
+    let len = addr_of!(ENTRY_COUNT).read();
+    &(*addr_of!(ENTRIES))[..len]
+}
+
+/// Clears all accumulated measurements.
+/// 
+/// # Safety
+/// No [`Scope`] may be dropped while the table is being cleared.
+pub unsafe fn reset() {
+    addr_of_mut!(ENTRY_COUNT).write(0);
+}
@@ -0,0 +1,284 @@
+//! Timer interrupt helpers, built on the Count and Compare registers.
+//! 
+//! The Count register increments at half the CPU clock rate. When it becomes equal to the Compare
+//! register, the timer interrupt (Cause.ip7) is raised. Writing to Compare is the only way to
+//! acknowledge the timer interrupt.
+
+use core::ops::{Add, Sub};
+use core::ptr::addr_of_mut;
+use crate::cp0::{cause, compare, count, interrupt_free, modify_status, set_compare};
+
+/// Number of Count register increments per second (half of the 93.75 MHz CPU clock).
+pub const COUNT_HZ: u32 = 46_875_000;
+
+/// Schedules the next timer interrupt `next_delta` Count cycles from now, acknowledging any pending
+/// timer interrupt in the process.
+/// 
+/// Returns whether a timer interrupt was pending before Compare was written. When called from the
+/// timer interrupt handler, `false` indicates a spurious wakeup.
+/// 
+/// Forgetting to write Compare in the timer interrupt handler leaves the interrupt asserted,
+/// causing the handler to be re-entered immediately after returning.
+#[inline(always)]
+pub unsafe fn rearm(next_delta: u32) -> bool {
+    let pending = cause().ip7();
+    set_compare(count().wrapping_add(next_delta));
+    
+    pending
+}
+
+/// Period of the timer started by [`start_periodic()`], or 0 if it isn't running.
+static mut PERIOD: u32 = 0;
+
+/// Starts a periodic timer interrupt, firing every `period_cycles` Count cycles.
+/// 
+/// The first interrupt is scheduled `period_cycles` from now, and the timer interrupt is unmasked
+/// (Status.im_timer). Interrupts still need to be enabled globally (Status.ie) to be taken. The
+/// timer interrupt handler must call [`rearm_periodic()`] to schedule the next tick.
+/// 
+/// # Panics
+/// Panics if `period_cycles` is 0.
+pub unsafe fn start_periodic(period_cycles: u32) {
+    assert_ne!(period_cycles, 0, "periodic timer period must be non-zero");
+    interrupt_free(|| {
+        addr_of_mut!(PERIOD).write(period_cycles);
+        set_compare(count().wrapping_add(period_cycles));
+        modify_status(|status| status.with_im_timer(true));
+    });
+}
+
+/// Schedules the next tick of the timer started by [`start_periodic()`], acknowledging the current
+/// one. Intended to be called from the timer interrupt handler.
+/// 
+/// Ticks are scheduled relative to the previous Compare value rather than the current Count, so
+/// interrupt latency doesn't accumulate as drift. If the handler ran so late that the next tick
+/// has already passed, it's instead scheduled a full period from now, and missed ticks are dropped.
+/// 
+/// Returns the same as [`rearm()`]. Does nothing except acknowledging the interrupt if the
+/// periodic timer isn't running.
+pub unsafe fn rearm_periodic() -> bool {
+    let period = addr_of_mut!(PERIOD).read();
+    if period == 0 {
+        return rearm(u32::MAX);
+    }
+    
+    let pending = cause().ip7();
+    let now = count();
+    let next = compare().wrapping_add(period);
+    if next.wrapping_sub(now) > period {
+        set_compare(now.wrapping_add(period));
+    } else {
+        set_compare(next);
+    }
+    
+    pending
+}
+
+/// Stops the timer started by [`start_periodic()`], by masking the timer interrupt (Status.im_timer).
+/// 
+/// Any pending timer interrupt is also acknowledged.
+pub unsafe fn stop() {
+    interrupt_free(|| {
+        modify_status(|status| status.with_im_timer(false));
+        addr_of_mut!(PERIOD).write(0);
+        rearm(u32::MAX);
+    });
+}
+
+/// Returns the period of the timer started by [`start_periodic()`], or `None` if it isn't running.
+#[must_use]
+pub fn period() -> Option<u32> {
+    match unsafe { addr_of_mut!(PERIOD).read() } {
+        0 => None,
+        period => Some(period),
+    }
+}
+
+/// Busy-waits for at least `cycles` Count register increments.
+/// 
+/// Wrapping of the Count register is handled, so any delay up to `u32::MAX` cycles (roughly 91
+/// seconds) is supported.
+#[inline]
+pub fn delay_cycles(cycles: u32) {
+    let start = count();
+    while count().wrapping_sub(start) < cycles {}
+}
+
+/// Busy-waits for at least `us` microseconds.
+/// 
+/// Delays longer than `u32::MAX` Count cycles are split into multiple waits.
+pub fn delay_us(us: u32) {
+    delay_ns((us as u64) * 1_000);
+}
+
+/// Busy-waits for at least `ns` nanoseconds, rounded up to the next Count cycle.
+/// 
+/// Delays too long to compute (over 190 years) saturate instead of overflowing.
+pub fn delay_ns(ns: u64) {
+    // 46.875 MHz is exactly 3/64 cycles per nanosecond
+    let mut cycles = ns.saturating_mul(3).saturating_add(63) / 64;
+    while cycles > 0 {
+        let chunk = cycles.min(u32::MAX as u64);
+        delay_cycles(chunk as u32);
+        cycles -= chunk;
+    }
+}
+
+/// A busy-wait delay provider backed by the Count register.
+/// 
+/// With the `embedded-hal` feature enabled, this implements `embedded_hal::delay::DelayNs`, allowing
+/// drivers written against embedded-hal to be used on the N64.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Delay;
+
+#[cfg(feature = "embedded-hal")]
+impl embedded_hal::delay::DelayNs for Delay {
+    #[inline]
+    fn delay_ns(&mut self, ns: u32) {
+        delay_ns(ns as u64);
+    }
+    
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        delay_us(us);
+    }
+    
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        delay_ns((ms as u64) * 1_000_000);
+    }
+}
+
+/// Last Count value observed by [`count64()`].
+static mut LAST_COUNT: u32 = 0;
+/// Number of times [`count64()`] has observed the Count register wrap around.
+static mut COUNT_WRAPS: u32 = 0;
+
+/// Returns the Count register, extended to 64 bits in software so it never wraps around.
+/// 
+/// Wrapping is detected by comparing against the previous call, so this must be called at least
+/// once per Count period (roughly 91 seconds) to stay accurate. The Count register starts at an
+/// unknown value on boot, so only differences between values are meaningful.
+pub fn count64() -> u64 {
+    interrupt_free(|| unsafe {
+        let now = count();
+        let last = addr_of_mut!(LAST_COUNT);
+        let wraps = addr_of_mut!(COUNT_WRAPS);
+        if now < last.read() {
+            wraps.write(wraps.read().wrapping_add(1));
+        }
+        last.write(now);
+        
+        ((wraps.read() as u64) << 32) | (now as u64)
+    })
+}
+
+/// A span of time, measured in Count register cycles.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration(u64);
+impl Duration {
+    /// A duration of zero.
+    pub const ZERO: Duration = Duration(0);
+    
+    /// Creates a duration from a number of Count cycles.
+    pub const fn from_cycles(cycles: u64) -> Self {
+        Self(cycles)
+    }
+    
+    /// Creates a duration from a number of microseconds, rounded up to the next Count cycle.
+    /// 
+    /// Durations too long to compute (over 190 years) saturate instead of overflowing.
+    pub const fn from_micros(us: u64) -> Self {
+        Self(us.saturating_mul(3000).saturating_add(63) / 64)
+    }
+    
+    /// Creates a duration from a number of milliseconds.
+    /// 
+    /// Durations too long to represent saturate to `u64::MAX` cycles.
+    pub const fn from_millis(ms: u64) -> Self {
+        Self(ms.saturating_mul(COUNT_HZ as u64 / 1000))
+    }
+    
+    /// Returns the number of Count cycles in this duration.
+    #[must_use]
+    pub const fn as_cycles(&self) -> u64 {
+        self.0
+    }
+    
+    /// Returns the number of whole nanoseconds in this duration.
+    #[must_use]
+    pub const fn as_nanos(&self) -> u64 {
+        // 46.875 MHz is exactly 64/3 nanoseconds per cycle
+        self.0 * 64 / 3
+    }
+    
+    /// Returns the number of whole microseconds in this duration.
+    #[must_use]
+    pub const fn as_micros(&self) -> u64 {
+        self.0 * 64 / 3000
+    }
+    
+    /// Returns the number of whole milliseconds in this duration.
+    #[must_use]
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / (COUNT_HZ as u64 / 1000)
+    }
+}
+impl Add for Duration {
+    type Output = Duration;
+    
+    fn add(self, rhs: Self) -> Self::Output {
+        Duration(self.0 + rhs.0)
+    }
+}
+impl Sub for Duration {
+    type Output = Duration;
+    
+    fn sub(self, rhs: Self) -> Self::Output {
+        Duration(self.0 - rhs.0)
+    }
+}
+
+/// A point in time, measured by the software extended Count register (see [`count64()`]).
+/// 
+/// ```no_run
+/// # use n64_pac::cp0::Instant;
+/// let start = Instant::now();
+/// // ...
+/// let micros = start.elapsed().as_micros();
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Instant(u64);
+impl Instant {
+    /// Returns the current point in time.
+    #[must_use]
+    pub fn now() -> Self {
+        Self(count64())
+    }
+    
+    /// Returns the time elapsed since this instant.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+    
+    /// Returns the time elapsed from `earlier` to this instant, or zero if `earlier` is later.
+    #[must_use]
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration(self.0.saturating_sub(earlier.0))
+    }
+}
+impl Add<Duration> for Instant {
+    type Output = Instant;
+    
+    fn add(self, rhs: Duration) -> Self::Output {
+        Instant(self.0 + rhs.0)
+    }
+}
+impl Sub for Instant {
+    type Output = Duration;
+    
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.duration_since(rhs)
+    }
+}
@@ -0,0 +1,223 @@
+//! High-level TLB mapping, built on the TLB instructions and registers.
+//! 
+//! Each TLB entry maps a pair of adjacent virtual pages of the same size: EntryLo0 maps the even
+//! page, and EntryLo1 the odd page. [`map()`] maps a single page, sharing an entry with the other
+//! page of its pair if that one is already mapped, so callers don't need to deal with the pairing.
+//! 
+//! The TLB's contents are undefined after a cold boot, so [`clear()`] should be called once before
+//! the first [`map()`]. Entries below the Wired register (see
+//! [`lock_tlb_entries()`][crate::cp0::lock_tlb_entries()]) are never used by [`map()`] or
+//! [`clear()`].
+//! 
+//! Non-global mappings use the address space ID currently in EntryHi.
+//! 
+//! # Safety
+//! All functions in this module overwrite the Index, EntryLo0, EntryLo1, and PageMask registers.
+//! EntryHi is restored before returning. None of them may be interrupted by code which also
+//! accesses the TLB.
+
+use core::ops::{BitOr, BitOrAssign};
+use crate::cp0::{entryhi, find_tlb_entry, read_tlb_entry, set_entryhi, set_entrylo0, set_entrylo1, set_index, set_pagemask, tlb_write_indexed, wired, CacheAlgorithm, EntryHiReg, EntryLoReg, IndexReg, PageMaskReg, PageSize};
+
+/// Number of entries in the VR4300's TLB.
+pub const TLB_ENTRIES: u8 = 32;
+
+/// Errors reported by the TLB mapping functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TlbError {
+    /// The page size is [`PageSize::Undefined`].
+    InvalidSize,
+    /// The virtual or physical address isn't aligned to the page size.
+    Unaligned,
+    /// The other page of the pair is already mapped, with a different page size or global flag.
+    PairMismatch,
+    /// Every TLB entry above the wired entries is in use.
+    Full,
+    /// The index is at or above [`TLB_ENTRIES`].
+    InvalidIndex,
+    /// No valid mapping exists for the address.
+    NotMapped,
+}
+
+/// Access flags of a mapped page.
+/// 
+/// ```
+/// # use n64_pac::cp0::tlb::PageFlags;
+/// let flags = PageFlags::WRITABLE | PageFlags::GLOBAL;
+/// assert!(flags.contains(PageFlags::WRITABLE));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PageFlags(u8);
+impl PageFlags {
+    /// Read-only, and only visible to the current address space ID.
+    pub const NONE: Self = Self(0);
+    /// Allows writes (EntryLo.D). Writing a page without this raises a TLB modification exception.
+    pub const WRITABLE: Self = Self(1 << 0);
+    /// Matches regardless of the address space ID (EntryLo.G).
+    pub const GLOBAL: Self = Self(1 << 1);
+    
+    /// Returns true if all flags in `other` are set.
+    #[must_use]
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for PageFlags {
+    type Output = Self;
+    
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOrAssign for PageFlags {
+    #[inline(always)]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Maps the page of `size` bytes at `vaddr` to `paddr`, using a free TLB entry (or the entry
+/// already mapping the other page of the pair). Returns the index of the entry used.
+/// 
+/// `vaddr` must be the 64-bit (sign-extended, if using 32-bit addressing) form of the address. Both
+/// addresses must be aligned to the page size. Mapping an address which is already mapped
+/// replaces the old mapping.
+/// 
+/// # Safety
+/// See the [module][self] documentation. Remapping memory which is in use, or mapping the same
+/// physical memory with different cache algorithms, can have undefined results.
+pub unsafe fn map(vaddr: u64, paddr: u32, size: PageSize, cache: CacheAlgorithm, flags: PageFlags) -> Result<u8, TlbError> {
+    let saved = entryhi();
+    let result = map_inner(None, vaddr, paddr, size, cache, flags, saved.asid());
+    set_entryhi(saved);
+    
+    result
+}
+
+/// Like [`map()`], but always writes the TLB entry at `index`, replacing its previous contents.
+/// The other page of the pair is left unmapped.
+/// 
+/// This is intended for wired entries. The caller must ensure no other entry maps the same
+/// address, as multiple matching entries cause a TLB shutdown (Status.TS).
+/// 
+/// # Safety
+/// See [`map()`].
+pub unsafe fn map_at(index: u8, vaddr: u64, paddr: u32, size: PageSize, cache: CacheAlgorithm, flags: PageFlags) -> Result<(), TlbError> {
+    if index >= TLB_ENTRIES {
+        return Err(TlbError::InvalidIndex);
+    }
+    
+    let saved = entryhi();
+    let result = map_inner(Some(index), vaddr, paddr, size, cache, flags, saved.asid());
+    set_entryhi(saved);
+    
+    result.map(|_| ())
+}
+
+/// Removes the mapping of the page containing `vaddr`, in the current address space.
+/// 
+/// Only the page containing `vaddr` is invalidated; the other page of its pair stays mapped. Once
+/// both pages are unmapped, the entry is freed for reuse by [`map()`].
+/// 
+/// # Safety
+/// See the [module][self] documentation.
+pub unsafe fn unmap(vaddr: u64) -> Result<(), TlbError> {
+    let saved = entryhi();
+    let result = unmap_inner(vaddr, saved.asid());
+    set_entryhi(saved);
+    
+    result
+}
+
+/// Invalidates every TLB entry at or above the Wired register, freeing them for [`map()`].
+/// 
+/// # Safety
+/// See the [module][self] documentation. Any memory accessed through the removed mappings
+/// becomes inaccessible.
+pub unsafe fn clear() {
+    let saved = entryhi();
+    for index in wired().wired()..TLB_ENTRIES {
+        write_entry(index, unused_entry_hi(index), EntryLoReg(0), EntryLoReg(0), PageMaskReg(0));
+    }
+    set_entryhi(saved);
+}
+
+unsafe fn map_inner(index: Option<u8>, vaddr: u64, paddr: u32, size: PageSize, cache: CacheAlgorithm, flags: PageFlags, asid: u8) -> Result<u8, TlbError> {
+    let bytes = size.bytes().ok_or(TlbError::InvalidSize)?;
+    if vaddr & (bytes as u64 - 1) != 0 || paddr & (bytes - 1) != 0 {
+        return Err(TlbError::Unaligned);
+    }
+    
+    let global = flags.contains(PageFlags::GLOBAL);
+    let page = EntryLoReg::for_page(paddr, cache, flags.contains(PageFlags::WRITABLE), true, global);
+    let invalid = EntryLoReg(0).with_global(global);
+    
+    let (index, mut lo0, mut lo1) = match index {
+        Some(index) => (index, invalid, invalid),
+        None => match find_tlb_entry(vaddr, asid) {
+            Some(index) => {
+                let entry = read_tlb_entry(index);
+                if entry.page_mask.mask() != size || entry.entry_lo0.global() != global {
+                    return Err(TlbError::PairMismatch);
+                }
+                (index, entry.entry_lo0, entry.entry_lo1)
+            }
+            None => (find_free().ok_or(TlbError::Full)?, invalid, invalid),
+        },
+    };
+    
+    if vaddr & bytes as u64 != 0 {
+        lo1 = page;
+    } else {
+        lo0 = page;
+    }
+    
+    // Bits 0..=12 aren't part of VPN2, and bits below the pair's size are ignored by the TLB
+    let vpn2 = vaddr & 0xC00000FF_FFFFE000 & !(bytes as u64 * 2 - 1);
+    write_entry(index, EntryHiReg(vpn2 | asid as u64), lo0, lo1, PageMaskReg(0).with_mask(size));
+    
+    Ok(index)
+}
+
+unsafe fn unmap_inner(vaddr: u64, asid: u8) -> Result<(), TlbError> {
+    let index = find_tlb_entry(vaddr, asid).ok_or(TlbError::NotMapped)?;
+    let entry = read_tlb_entry(index);
+    let bytes = entry.page_mask.mask().bytes().unwrap_or(0x1000);
+    
+    let (mut lo0, mut lo1) = (entry.entry_lo0, entry.entry_lo1);
+    let page = if vaddr & bytes as u64 != 0 { &mut lo1 } else { &mut lo0 };
+    if !page.valid() {
+        return Err(TlbError::NotMapped);
+    }
+    *page = page.with_valid(false);
+    
+    let entry_hi = if lo0.valid() || lo1.valid() { entry.entry_hi } else { unused_entry_hi(index) };
+    write_entry(index, entry_hi, lo0, lo1, entry.page_mask);
+    
+    Ok(())
+}
+
+/// Returns the index of the first entry at or above Wired with both pages invalid.
+unsafe fn find_free() -> Option<u8> {
+    (wired().wired()..TLB_ENTRIES).find(|&index| {
+        let entry = read_tlb_entry(index);
+        !entry.entry_lo0.valid() && !entry.entry_lo1.valid()
+    })
+}
+
+/// Returns a distinct EntryHi within KSEG0 (which is never translated) for an unused entry, so it
+/// can never match an address, or duplicate another entry.
+fn unused_entry_hi(index: u8) -> EntryHiReg {
+    EntryHiReg(0xFFFFFFFF_80000000 | ((index as u64) << 13))
+}
+
+unsafe fn write_entry(index: u8, entry_hi: EntryHiReg, lo0: EntryLoReg, lo1: EntryLoReg, page_mask: PageMaskReg) {
+    set_index(IndexReg(0).with_index(index));
+    set_entryhi(entry_hi);
+    set_entrylo0(lo0);
+    set_entrylo1(lo1);
+    set_pagemask(page_mask);
+    tlb_write_indexed();
+}
@@ -1,5 +1,6 @@
 //! FPU - Coprocessor 1
 
+#[cfg(target_arch = "mips")]
 use core::arch::asm;
 use core::marker::PhantomData;
 use num_enum::{FromPrimitive, IntoPrimitive};
@@ -11,6 +12,7 @@ macro_rules! cp1fn_ro {
     ($reg:ident, $width:ident, $index:literal, $datatype:ident) => {
         paste::paste! {
             #[doc = concat!("Reads from CP1 register ", stringify!($index), ".")]
+            #[must_use]
             #[inline(always)]
             pub fn $reg() -> $datatype {
                 [<read_ $width>]::<$index>().into()
@@ -46,6 +48,9 @@ macro_rules! cp1fn_rw {
 
 /// A zero-sized struct for accessing CP1 registers via methods.
 /// 
+/// Every public function of the [module][crate::cp1] has a method of the same name. Methods are
+/// `unsafe` where the function has requirements beyond register access.
+/// 
 /// See [`Cp1::new()`] for usage details.
 pub struct Cp1 {
     _marker: PhantomData<*const ()>
@@ -57,8 +62,12 @@ impl Cp1 {
     /// But for unrestricted, unsafe, access, this struct provides a method-based version to the
     /// static functions available at the [module][crate::cp1] level.
     /// 
+    /// Reading CP1 registers is always safe, so read-only code doesn't need this struct at all; the
+    /// read functions at the [module][crate::cp1] level are safe to call. Only the module level
+    /// write and modify functions are `unsafe`.
+    /// 
     /// # Safety
-    /// This provides unrestricted access to memory mapped registers. Data races _could_ occur if writing
+    /// This provides unrestricted write access to CP1 registers. Data races _could_ occur if writing
     /// to a register in both regular code and inside interrupt handlers.
     /// 
     /// This is especially problematic if performing a read-modify-write operation; an interrupt
@@ -71,13 +80,130 @@ impl Cp1 {
     
     cpxmethod_ro!(revision_implementation, ImplementationRevisionReg);
     cpxmethod_rw!(control_status, ControlStatusReg);
+    
+    cpxmethod_fn!(unsafe fn take_flags() -> FpuFlags);
+    cpxmethod_fn!(unsafe fn set_flush_denormals(enable: bool));
+    cpxmethod_fn!(#[must_use] fn flush_denormals() -> bool);
+    cpxmethod_fn!(unsafe fn on_unimplemented() -> bool);
+    cpxmethod_fn!(unsafe fn with_rounding_mode<F: FnOnce() -> R, R>(mode: RoundingMode, func: F) -> R);
+    cpxmethod_fn!(#[must_use] fn read_u32<const INDEX: u32>() -> u32);
+    cpxmethod_fn!(#[must_use] fn read_u64<const INDEX: u32>() -> u64);
+    cpxmethod_fn!(#[must_use] fn read_fcr<const INDEX: u32>() -> u32);
+    cpxmethod_fn!(unsafe fn write_u32<const INDEX: u32>(value: u32));
+    cpxmethod_fn!(unsafe fn write_u64<const INDEX: u32>(value: u64));
+    cpxmethod_fn!(unsafe fn write_u64_from<const INDEX: u32>(value: &u64));
+    cpxmethod_fn!(fn read_u64_into<const INDEX: u32>(out: &mut u64));
+    cpxmethod_fn!(unsafe fn write_fcr<const INDEX: u32>(value: u32));
 }
 
 cp1fn_ro!(revision_implementation, u32, 0, ImplementationRevisionReg);
 cp1fn_rw!(control_status, u32, 31, ControlStatusReg);
 
+/// Reads the accumulated (sticky) FPU exception flags from FCR31, and clears them.
+/// 
+/// When an FPU exception's trap isn't enabled, the operation completes and only sets the matching
+/// flag, which stays set until cleared. Calling this before and after a block of code reveals
+/// whether any operation in the block lost precision, overflowed, etc.
+/// 
+/// # Safety
+/// Flags set by an interrupt handler between the read and the write will be lost.
+#[inline]
+pub unsafe fn take_flags() -> FpuFlags {
+    let reg = control_status();
+    set_control_status(reg.with_flags(0));
+    
+    FpuFlags(reg.flags())
+}
+
+/// Sets or clears FCR31.FS, controlling whether denormalized results are flushed.
+/// 
+/// The VR4300 has no hardware support for denormalized numbers. With FS clear, any operation which
+/// would produce a denormalized result raises an unimplemented operation exception instead, which
+/// can't be masked. With FS set, such results are flushed to zero (or the smallest normal number,
+/// depending on the rounding mode), as long as the underflow and inexact exceptions are disabled.
+/// 
+/// Denormalized *operands* raise an unimplemented operation exception regardless of FS. See
+/// [`on_unimplemented()`] for detecting that case.
+#[inline(always)]
+pub unsafe fn set_flush_denormals(enable: bool) {
+    modify_control_status(|reg| reg.with_fs(enable));
+}
+
+/// Returns whether denormalized results are flushed, as set by [`set_flush_denormals()`].
+#[must_use]
+#[inline(always)]
+pub fn flush_denormals() -> bool {
+    control_status().fs()
+}
+
+/// Checks whether a floating point exception was an unimplemented operation, and clears the cause
+/// bits of FCR31 if so.
+/// 
+/// Intended to be called from the handler of [`ExceptionCode::FloatingPoint`][crate::cp0::ExceptionCode::FloatingPoint]
+/// exceptions. Unimplemented operations are raised by denormalized or NaN operands, and by
+/// denormalized results while FS is clear. Unless the handler emulates the instruction and
+/// advances EPC past it, returning from the exception executes the instruction again, trapping
+/// forever.
+/// 
+/// The cause bits are cleared because writing FCR31 with a cause bit set (which `ctc1` does when
+/// restoring the FPU state) raises the exception again. Returns `false` without changing anything
+/// if the exception had a different cause.
+#[inline]
+pub unsafe fn on_unimplemented() -> bool {
+    let reg = control_status();
+    if !reg.cause_unimplemented() {
+        return false;
+    }
+    
+    set_control_status(reg.with_causes(0));
+    true
+}
+
+/// Runs `func` with the FPU rounding mode temporarily set to `mode`, then restores the previous mode.
+/// 
+/// Only the rounding mode bits of FCR31 are changed; any flags raised by `func` are kept.
+/// 
+/// # Safety
+/// Changes made to the rounding mode by an interrupt handler while `func` runs will be lost.
+#[inline]
+pub unsafe fn with_rounding_mode<F: FnOnce() -> R, R>(mode: RoundingMode, func: F) -> R {
+    let _guard = RoundingModeGuard::new(mode);
+    func()
+}
+
+/// Sets the FPU rounding mode, and restores the previous mode when dropped.
+/// 
+/// See [`with_rounding_mode()`] for a closure-based version.
+#[must_use = "the previous rounding mode is restored as soon as the guard is dropped"]
+pub struct RoundingModeGuard {
+    previous: RoundingMode,
+}
+impl RoundingModeGuard {
+    /// Sets the FPU rounding mode to `mode`, remembering the current mode.
+    /// 
+    /// # Safety
+    /// Changes made to the rounding mode by an interrupt handler while the guard exists will be
+    /// lost when it's dropped. Guards must be dropped in the reverse order they were created.
+    #[inline]
+    pub unsafe fn new(mode: RoundingMode) -> Self {
+        let previous = control_status().rm();
+        modify_control_status(|reg| reg.with_rm(mode));
+        
+        Self { previous }
+    }
+}
+impl Drop for RoundingModeGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let previous = self.previous;
+        unsafe { modify_control_status(|reg| reg.with_rm(previous)); }
+    }
+}
+
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ImplementationRevisionReg(pub u32): Debug {
         /// Processor revision number
         pub revision: u8 [ro] @ 0..=7,
@@ -86,6 +212,7 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ImplementationRevisionReg, u32);
+impl_pod!(ImplementationRevisionReg);
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -114,7 +241,9 @@ pub enum RoundingMode {
 }
 
 bitfield! {
+    #[must_use]
     #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ControlStatusReg(pub u32): Debug {
         /// Rounding mode used for all float operations
         pub rm: u8 [RoundingMode] @ 0..=1,
@@ -149,12 +278,141 @@ bitfield! {
     }
 }
 derive_tofrom_primitive!(ControlStatusReg, u32);
+impl_pod!(ControlStatusReg);
+
+bitfield! {
+    /// Accumulated FPU exception flags, as returned by [`take_flags()`].
+    /// 
+    /// Uses the same bit order as [`ControlStatusReg::flags()`].
+    #[must_use]
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct FpuFlags(pub u8): Debug {
+        pub inexact: bool @ 0,
+        pub underflow: bool @ 1,
+        pub overflow: bool @ 2,
+        pub divzero: bool @ 3,
+        pub invalid: bool @ 4,
+    }
+}
+impl_pod!(FpuFlags);
+impl FpuFlags {
+    /// Returns `true` if any flag is set.
+    #[must_use]
+    pub fn any(&self) -> bool {
+        self.0 & 0x1F != 0
+    }
+}
 
 
 
+/// Every CP1 register, for tools which need to enumerate or access registers generically, such as a
+/// register browser or GDB stub.
+/// 
+/// The floating-point registers (`f0` to `f31`) and the control registers use separate index spaces,
+/// so [`Cp1Register::index()`] alone doesn't identify a register.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Cp1Register {
+    /// Floating-point general purpose register `f0` to `f31`
+    Fpr(u8),
+    /// Control register 0
+    ImplementationRevision,
+    /// Control register 31
+    ControlStatus,
+}
+impl Cp1Register {
+    /// Returns an iterator over all registers: `f0` to `f31`, followed by the control registers.
+    pub fn all() -> impl Iterator<Item = Cp1Register> {
+        (0..32).map(Self::Fpr).chain([Self::ImplementationRevision, Self::ControlStatus])
+    }
+    
+    /// Returns the register's name.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        const FPR_NAMES: [&str; 32] = ["f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15", "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23", "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31"];
+        
+        match self {
+            Self::Fpr(index) => FPR_NAMES[(index & 31) as usize],
+            Self::ImplementationRevision => "FCR0",
+            Self::ControlStatus => "FCR31",
+        }
+    }
+    
+    /// Returns the register's index, as used by the `mfc1`/`mtc1` instructions for floating-point
+    /// registers, or the `cfc1`/`ctc1` instructions for control registers.
+    #[must_use]
+    pub fn index(self) -> u32 {
+        match self {
+            Self::Fpr(index) => (index & 31) as u32,
+            Self::ImplementationRevision => 0,
+            Self::ControlStatus => 31,
+        }
+    }
+    
+    /// Returns `true` if the register can be written. Only control register 0 is read-only.
+    #[must_use]
+    pub fn is_writable(self) -> bool {
+        self != Self::ImplementationRevision
+    }
+    
+    /// Reads the register's raw value, zero-extending the 32-bit control registers.
+    /// 
+    /// Floating-point registers are read as 64 bits using `dmfc1`. When the Status.FR bit is
+    /// cleared, only the values read from even registers are meaningful.
+    #[must_use]
+    pub fn read(self) -> u64 {
+        match self {
+            Self::Fpr(index) => read_fpr(index),
+            Self::ImplementationRevision => read_fcr::<0>() as u64,
+            Self::ControlStatus => read_fcr::<31>() as u64,
+        }
+    }
+}
+
+/// Reads floating-point register `index` (masked to 0 to 31), as 64 bits.
+fn read_fpr(index: u8) -> u64 {
+    match index & 31 {
+        0 => read_u64::<0>(),
+        1 => read_u64::<1>(),
+        2 => read_u64::<2>(),
+        3 => read_u64::<3>(),
+        4 => read_u64::<4>(),
+        5 => read_u64::<5>(),
+        6 => read_u64::<6>(),
+        7 => read_u64::<7>(),
+        8 => read_u64::<8>(),
+        9 => read_u64::<9>(),
+        10 => read_u64::<10>(),
+        11 => read_u64::<11>(),
+        12 => read_u64::<12>(),
+        13 => read_u64::<13>(),
+        14 => read_u64::<14>(),
+        15 => read_u64::<15>(),
+        16 => read_u64::<16>(),
+        17 => read_u64::<17>(),
+        18 => read_u64::<18>(),
+        19 => read_u64::<19>(),
+        20 => read_u64::<20>(),
+        21 => read_u64::<21>(),
+        22 => read_u64::<22>(),
+        23 => read_u64::<23>(),
+        24 => read_u64::<24>(),
+        25 => read_u64::<25>(),
+        26 => read_u64::<26>(),
+        27 => read_u64::<27>(),
+        28 => read_u64::<28>(),
+        29 => read_u64::<29>(),
+        30 => read_u64::<30>(),
+        31 => read_u64::<31>(),
+        _ => unreachable!(),
+    }
+}
+
+
 #[inline(always)]
 pub fn read_u32<const INDEX: u32>() -> u32 {
     let value: u32;
+    #[cfg(target_arch = "mips")]
     unsafe {
         asm!("
             .set noat
@@ -164,6 +422,10 @@ pub fn read_u32<const INDEX: u32>() -> u32 {
         cp_reg = const INDEX
         );
     }
+    #[cfg(not(target_arch = "mips"))]
+    {
+        value = crate::host_unsupported(());
+    }
     
     value
 }
@@ -172,6 +434,7 @@ pub fn read_u32<const INDEX: u32>() -> u32 {
 pub fn read_u64<const INDEX: u32>() -> u64 {
     let value_lo: u32;
     let value_hi: u32;
+    #[cfg(target_arch = "mips")]
     unsafe {
         asm!("
             .set noat
@@ -185,6 +448,10 @@ pub fn read_u64<const INDEX: u32>() -> u64 {
         cp_reg = const INDEX
         );
     }
+    #[cfg(not(target_arch = "mips"))]
+    {
+        (value_lo, value_hi) = crate::host_unsupported(());
+    }
     
     ((value_hi as u64) << 32) | (value_lo as u64)
 }
@@ -195,6 +462,7 @@ pub fn read_u64<const INDEX: u32>() -> u64 {
 #[inline(always)]
 pub fn read_fcr<const INDEX: u32>() -> u32 {
     let value: u32;
+    #[cfg(target_arch = "mips")]
     unsafe {
         asm!("
             .set noat
@@ -204,12 +472,17 @@ pub fn read_fcr<const INDEX: u32>() -> u32 {
         cp_reg = const INDEX
         );
     }
+    #[cfg(not(target_arch = "mips"))]
+    {
+        value = crate::host_unsupported(());
+    }
     
     value
 }
 
 #[inline(always)]
 pub unsafe fn write_u32<const INDEX: u32>(value: u32) {
+    #[cfg(target_arch = "mips")]
     asm!("
         .set noat
         mtc1 {gpr}, ${cp_reg}
@@ -218,10 +491,13 @@ pub unsafe fn write_u32<const INDEX: u32>(value: u32) {
     gpr = in(reg) value,
     cp_reg = const INDEX
     );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
 }
 
 #[inline(always)]
 pub unsafe fn write_u64<const INDEX: u32>(value: u64) {
+    #[cfg(target_arch = "mips")]
     asm!("
         .set noat
         dsll32 {tmp}, {hi}, 0
@@ -237,6 +513,52 @@ pub unsafe fn write_u64<const INDEX: u32>(value: u64) {
     hi = in(reg) ((value >> 32) as u32),
     cp_reg = const INDEX
     );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
+}
+
+/// Writes a 64-bit value from memory into a CP1 floating-point register, using a single `ldc1`.
+/// 
+/// [`write_u64()`] has to assemble the value from two 32-bit GPRs, as the 32-bit ABI doesn't allow
+/// a 64-bit GPR operand, which costs four extra instructions. When the value is already in memory,
+/// such as when restoring a saved FPU context, loading it directly is cheaper.
+/// 
+/// # Safety
+/// With the Status.FR bit cleared, only even registers can be used for 64-bit accesses, where the
+/// odd register receives the upper half of the value. The behavior of odd registers with FR
+/// cleared is undefined. With FR set, all 32 registers are independent 64-bit registers.
+#[inline(always)]
+pub unsafe fn write_u64_from<const INDEX: u32>(value: &u64) {
+    #[cfg(target_arch = "mips")]
+    asm!("
+        .set noat
+        ldc1 ${cp_reg}, 0({ptr})
+    ",
+    ptr = in(reg) value as *const u64,
+    cp_reg = const INDEX
+    );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
+}
+
+/// Reads a 64-bit value from a CP1 floating-point register into memory, using a single `sdc1`.
+/// 
+/// This is the counterpart to [`write_u64_from()`], for saving an FPU context without the shifts
+/// required by [`read_u64()`]. The same FR pairing rules apply.
+#[inline(always)]
+pub fn read_u64_into<const INDEX: u32>(out: &mut u64) {
+    #[cfg(target_arch = "mips")]
+    unsafe {
+        asm!("
+            .set noat
+            sdc1 ${cp_reg}, 0({ptr})
+        ",
+        ptr = in(reg) out as *mut u64,
+        cp_reg = const INDEX
+        );
+    }
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(out);
 }
 
 /// Write CP1 control register
@@ -244,12 +566,15 @@ pub unsafe fn write_u64<const INDEX: u32>(value: u64) {
 /// Only registers 0 (Implementation/Revision) and 31 (Control/Status) are known to exist.
 #[inline(always)]
 pub unsafe fn write_fcr<const INDEX: u32>(value: u32) {
+    #[cfg(target_arch = "mips")]
     asm!("
         .set noat
-        mtc1 {gpr}, ${cp_reg}
+        ctc1 {gpr}, ${cp_reg}
         nop
     ",
     gpr = in(reg) value,
     cp_reg = const INDEX
     );
+    #[cfg(not(target_arch = "mips"))]
+    crate::host_unsupported::<_, ()>(value);
 }
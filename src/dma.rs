@@ -0,0 +1,50 @@
+//! Type-states shared by the DMA engine tokens
+//! 
+//! Each DMA engine with a type-state token (currently [`pi::Dma`][crate::pi::Dma] and
+//! [`si::Dma`][crate::si::Dma]) is borrowed from its interface in the [`Idle`] state. Starting a
+//! transfer consumes the idle token and returns a [`Busy`] token, which must be waited on (or
+//! polled until completion) to get the idle token back. Starting a second transfer while one is
+//! still in flight is therefore a compile error, rather than silent data corruption.
+//! 
+//! ```no_run
+//! # use n64_pac::Hardware;
+//! let mut hw = Hardware::take().unwrap();
+//! let dma = hw.pi.dma();
+//! let dma = unsafe { dma.start_read(0x80100000, 0x10001000, 0x1000) };
+//! // dma.start_read(...) here would not compile, as `dma` is busy.
+//! let dma = dma.wait();
+//! ```
+//! 
+//! Transfers can also be started without waiting at all, with a callback run from the interface's
+//! interrupt once they finish, such as [`pi::start_dma_read_notify()`][crate::pi::start_dma_read_notify()].
+
+use core::cell::Cell;
+
+/// Marker for a DMA engine with no transfer in progress.
+pub struct Idle;
+
+/// Marker for a DMA engine with a transfer in progress.
+pub struct Busy;
+
+/// Holds the callback of a DMA transfer started with a completion notification, until the
+/// interface's interrupt handler takes it.
+pub(crate) struct Completion(Cell<Option<fn()>>);
+// SAFETY: The N64 has a single CPU core, and the callback is only replaced with interrupts disabled.
+unsafe impl Sync for Completion {}
+impl Completion {
+    pub(crate) const fn new() -> Self {
+        Self(Cell::new(None))
+    }
+    
+    /// Stores `on_done`, replacing any callback which hasn't been taken yet.
+    pub(crate) fn set(&self, on_done: fn()) {
+        crate::cp0::interrupt_free(|| self.0.set(Some(on_done)));
+    }
+    
+    /// Removes and calls the stored callback, if any.
+    pub(crate) fn complete(&self) {
+        if let Some(on_done) = self.0.take() {
+            on_done();
+        }
+    }
+}
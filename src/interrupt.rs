@@ -0,0 +1,124 @@
+//! Interrupt dispatch
+//! 
+//! The CPU has a single general exception vector, shared by interrupts and every other kind of
+//! exception. All RCP interrupts arrive on the same CPU interrupt line (Cause.IP2), and must be told
+//! apart by reading `MI_INTERRUPT`. The [`interrupt_handlers!`][crate::interrupt_handlers] macro
+//! generates a dispatcher which performs this decoding, and acknowledges each interrupt at its
+//! source.
+
+/// Generates a general exception dispatcher, which calls the named handler for each pending interrupt.
+/// 
+/// ```ignore
+/// n64_pac::interrupt_handlers! {
+///     fn handle_exception;
+///     vi => on_vi,
+///     ai => on_ai,
+///     timer => on_timer,
+///     exception => on_exception,
+/// }
+/// ```
+/// 
+/// The generated `unsafe fn handle_exception()` should be called by the general exception vector,
+/// after the interrupted context has been saved. It reads the exception state once using
+/// [`cp0::exception_info()`][crate::cp0::exception_info()], then:
+/// - For non-interrupt exceptions, calls the `exception` handler with a reference to the
+///   [`ExceptionInfo`][crate::cp0::ExceptionInfo].
+/// - For interrupts, calls the handler of every pending (and unmasked) source.
+/// 
+/// Supported sources:
+/// 
+/// | Source      | Triggered by                     | Acknowledged by                    |
+/// |:-----------:|:---------------------------------|:-----------------------------------|
+/// | `sp`        | MI, SP interrupt                 | `SP_STATUS` clear interrupt bit    |
+/// | `si`        | MI, SI DMA finished              | Write to `SI_STATUS`               |
+/// | `ai`        | MI, AI buffer started            | Write to `AI_STATUS`               |
+/// | `vi`        | MI, `VI_V_INTR` line reached     | [`vi::acknowledge_interrupt()`][crate::vi::acknowledge_interrupt()] |
+/// | `pi`        | MI, PI DMA finished              | [`pi::acknowledge_interrupt()`][crate::pi::acknowledge_interrupt()] |
+/// | `dp`        | MI, RDP full sync                | `MI_MODE` clear DP interrupt bit   |
+/// | `pre_nmi`   | Cause.IP4, reset button pressed  | Can't be acknowledged (see [`cp0::reset_pending()`][crate::cp0::reset_pending()]) |
+/// | `timer`     | Cause.IP7, Count reached Compare | Handler must write Compare         |
+/// 
+/// RCP interrupts are acknowledged before their handler is called, so that a new interrupt raised
+/// while the handler runs (such as by starting another DMA) isn't lost. Handlers take no arguments,
+/// except for `exception`. Sources without a handler are neither called nor acknowledged.
+#[macro_export]
+macro_rules! interrupt_handlers {
+    (fn $name:ident; $($source:ident => $handler:path),* $(,)?) => {
+        /// General exception dispatcher generated by `n64_pac::interrupt_handlers!`.
+        pub unsafe fn $name() {
+            let info = $crate::cp0::exception_info();
+            
+            if info.cause.exception_code() != $crate::cp0::ExceptionCode::Interrupt {
+                $( $crate::__interrupt_handlers_exception!($source, $handler, info); )*
+                return;
+            }
+            
+            let pending = if info.cause.ip2() {
+                $crate::mi::interrupt().sources() & $crate::mi::mask().sources()
+            } else {
+                $crate::mi::InterruptSet::empty()
+            };
+            let _ = pending;
+            $( $crate::__interrupt_handlers_source!($source, $handler, info, pending); )*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __interrupt_handlers_exception {
+    (exception, $handler:path, $info:ident) => { $handler(&$info); };
+    ($source:ident, $handler:path, $info:ident) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __interrupt_handlers_source {
+    (exception, $handler:path, $info:ident, $pending:ident) => {};
+    (sp, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::SP) {
+            $crate::sp::set_status($crate::sp::StatusRegWrite(0).clear_interrupt());
+            $handler();
+        }
+    };
+    (si, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::SI) {
+            $crate::si::set_status($crate::si::StatusReg(0));
+            $handler();
+        }
+    };
+    (ai, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::AI) {
+            $crate::ai::set_status($crate::ai::StatusReg(0));
+            $handler();
+        }
+    };
+    (vi, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::VI) {
+            $crate::vi::acknowledge_interrupt();
+            $handler();
+        }
+    };
+    (pi, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::PI) {
+            $crate::pi::acknowledge_interrupt();
+            $handler();
+        }
+    };
+    (dp, $handler:path, $info:ident, $pending:ident) => {
+        if $pending.contains($crate::mi::InterruptSet::DP) {
+            $crate::mi::set_mode($crate::mi::ModeRegWrite(0).clear_dp_interrupt());
+            $handler();
+        }
+    };
+    (pre_nmi, $handler:path, $info:ident, $pending:ident) => {
+        if $info.cause.ip4() {
+            $handler();
+        }
+    };
+    (timer, $handler:path, $info:ident, $pending:ident) => {
+        if $info.cause.ip7() {
+            $handler();
+        }
+    };
+}
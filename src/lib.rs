@@ -77,22 +77,44 @@
 //! 
 //! vi.ctrl.modify(|value| value.with_depth(ColorDepth::BPP32));
 //! ```
+//! 
+//! # Memory model
+//! All memory mapped register accesses made through [`RW`], [`RO`], and [`WO`] are volatile. This
+//! guarantees the compiler will neither elide them, nor reorder them relative to other volatile
+//! accesses. It does **not** act as a hardware barrier; a store may still be sitting in the CPU's
+//! write buffer when the next instruction executes, and non-volatile accesses (such as writes to a
+//! DMA buffer) may be reordered around it by the compiler.
+//! 
+//! When the caller must know a write has reached the device before continuing (for example,
+//! before relying on a DMA having been started), use [`RW::write_with_barrier()`].
+//! 
+//! # Building for the host
+//! The crate also builds for targets other than the N64's VR4300 (`target_arch = "mips"`), so that
+//! `cargo doc`, `cargo check`, and host tests of the bitfield types work on a normal development
+//! machine. Functions which execute CPU instructions (such as CP0/CP1 register accesses and TLB
+//! operations) panic when called on the host. Memory mapped register accesses still compile, but
+//! refer to the console's physical addresses, so must not be performed on the host.
 
 #![no_std]
-#![feature(asm_experimental_arch)]
-#![feature(asm_const)]
+#![cfg_attr(target_arch = "mips", feature(asm_experimental_arch))]
+#![cfg_attr(target_arch = "mips", feature(asm_const))]
 
+use core::cell::Cell;
+use core::marker::PhantomData;
 use crate::ai::AudioInterface;
 use crate::cp0::Cp0;
 use crate::cp1::Cp1;
 use crate::mi::MipsInterface;
 use crate::pi::PeripheralInterface;
+use crate::ri::RdramInterface;
 use crate::si::SerialInterface;
+use crate::sp::SignalProcessor;
 use crate::vi::VideoInterface;
 
 macro_rules! regfn_ro {
     ($block:ident, $reg:ident, $reg_name:expr, $datatype:ident) => {
         #[doc = concat!("Creates a temporary pointer to the [`", stringify!($block), "`], and reads data from its ", stringify!($reg_name), " register.")]
+        #[must_use]
         #[inline(always)]
         pub fn $reg() -> $datatype {
             unsafe { $block::new().$reg.read() }
@@ -100,9 +122,10 @@ macro_rules! regfn_ro {
     };
 }
 macro_rules! regfn_wo {
-    ($block:ident, $reg:ident, $reg_name:expr, $datatype:ident) => {
+    ($block:ident, $reg:ident, $reg_name:expr, $datatype:ident $(, $(#[$setter_attr:meta])+)?) => {
         paste::paste! {
             #[doc = concat!("Creates a temporary pointer to the [`", stringify!($block), "`], and writes data to its ", stringify!($reg_name), " register.")]
+            $(#[doc = ""] $(#[$setter_attr])+)?
             #[inline(always)]
             pub unsafe fn [<set_ $reg>](data: $datatype) {
                 $block::new().$reg.write(data);
@@ -111,9 +134,9 @@ macro_rules! regfn_wo {
     }
 }
 macro_rules! regfn_rw {
-    ($block:ident, $reg:ident, $reg_name:expr, $datatype:ident) => {
+    ($block:ident, $reg:ident, $reg_name:expr, $datatype:ident $(, $(#[$setter_attr:meta])+)?) => {
         regfn_ro!($block, $reg, $reg_name, $datatype);
-        regfn_wo!($block, $reg, $reg_name, $datatype);
+        regfn_wo!($block, $reg, $reg_name, $datatype $(, $(#[$setter_attr])+)?);
         
         paste::paste! {
             #[doc = concat!("Creates a temporary pointer to the [`", stringify!($block), "`], reads data from its ", stringify!($reg_name), " register, modifies the data, then finally writes back into the register.")]
@@ -129,9 +152,10 @@ macro_rules! regfn_ro_union {
     ($block:ident, $reg:ident, $reg_name:expr, $uniontype:ident) => {
         paste::paste! {
             #[doc = concat!("Creates a temporary pointer to the [`", stringify!($block), "`], and reads data from its ", stringify!($reg_name), " register.")]
+            #[must_use]
             #[inline(always)]
             pub fn $reg() -> [<$uniontype Read>] {
-                unsafe { $block::new().$reg.read().read }
+                unsafe { $block::new().$reg.read().decoded() }
             }
         }
     };
@@ -154,8 +178,98 @@ macro_rules! regfn_rw_union {
     }
 }
 
+/// Builds a [`monitor::Register`] for the monitor and [`registry`], from the register functions of
+/// the module it's used in (such as `ctrl()` and `set_ctrl()`). The register's address is taken
+/// from its field of `$block`, or from `[$place]` if it's nested within the block.
+/// 
+/// Typed registers are converted to and from raw values through their `.0` field, and union
+/// registers through their `Read` and `Write` halves. The `@` form takes each part explicitly, for
+/// registers which need other functions.
+macro_rules! monitor_reg {
+    ($block:ident, $reg:ident, ro) => {
+        monitor_reg!(@ $block, $reg, [$reg], read: Some($reg), write: None, decode: None, read_side_effects: false)
+    };
+    ($block:ident, $reg:ident, ro, $datatype:ident) => {
+        monitor_reg!(@ $block, $reg, [$reg],
+            read: Some(|| $reg().0),
+            write: None,
+            decode: Some(|raw, out| write!(out, "{:?}", $datatype(raw))),
+            read_side_effects: false)
+    };
+    ($block:ident, $reg:ident, wo) => {
+        paste::paste! {
+            monitor_reg!(@ $block, $reg, [$reg],
+                read: None,
+                write: Some([<set_ $reg>]),
+                decode: None,
+                read_side_effects: false)
+        }
+    };
+    ($block:ident, $reg:ident, rw) => {
+        monitor_reg!($block, $reg, [$reg], rw)
+    };
+    ($block:ident, $reg:ident, [$($place:tt)+], rw) => {
+        paste::paste! {
+            monitor_reg!(@ $block, $reg, [$($place)+],
+                read: Some($reg),
+                write: Some([<set_ $reg>]),
+                decode: None,
+                read_side_effects: false)
+        }
+    };
+    ($block:ident, $reg:ident, rw, $datatype:ident) => {
+        paste::paste! {
+            monitor_reg!(@ $block, $reg, [$reg],
+                read: Some(|| $reg().0),
+                write: Some(|data| unsafe { [<set_ $reg>]($datatype(data)) }),
+                decode: Some(|raw, out| write!(out, "{:?}", $datatype(raw))),
+                read_side_effects: false)
+        }
+    };
+    ($block:ident, $reg:ident, rw_union, $uniontype:ident) => {
+        paste::paste! {
+            monitor_reg!(@ $block, $reg, [$reg],
+                read: Some(|| $reg().0),
+                write: Some(|data| unsafe { [<set_ $reg>]([<$uniontype Write>](data)) }),
+                decode: Some(|raw, out| write!(out, "{:?}", [<$uniontype Read>](raw))),
+                read_side_effects: false)
+        }
+    };
+    (@ $block:ident, $reg:ident, [$($place:tt)+], read: $read:expr, write: $write:expr, decode: $decode:expr, read_side_effects: $side_effects:literal) => {
+        crate::monitor::Register {
+            name: stringify!($reg),
+            addr: || {
+                use crate::monitor::Mmio;
+                unsafe { $block::new().$($place)+.addr() }
+            },
+            read: $read,
+            write: $write,
+            read_side_effects: $side_effects,
+            decode: $decode,
+        }
+    };
+}
+/// Generates the module's `REGISTERS` table from [`monitor_reg!`] entries, and its `hexdump()`
+/// function which prints them. Extra documentation for `hexdump()` can follow the table.
+macro_rules! monitor_table {
+    ($iface_name:literal, [$($entry:expr),* $(,)?] $(, $(#[$hexdump_attr:meta])+)?) => {
+        /// Registers printed by [`hexdump()`], and accessed by name by the [`monitor`][crate::monitor]
+        /// and [`registry`][crate::registry].
+        pub(crate) static REGISTERS: &[crate::monitor::Register] = &[$($entry),*];
+        
+        #[doc = concat!("Prints every readable ", $iface_name, " register to `out`, one per line, as its offset, name, raw value, and decoded fields.")]
+        #[doc = ""]
+        #[doc = "Each register is read exactly once. Write-only registers are skipped."]
+        $(#[doc = ""] $(#[$hexdump_attr])+)?
+        pub fn hexdump(out: &mut impl core::fmt::Write) -> core::fmt::Result {
+            crate::monitor::hexdump(REGISTERS, out)
+        }
+    };
+}
+
 macro_rules! cpxmethod_ro {
     ($reg:ident, $datatype:ident) => {
+        #[must_use]
         pub fn $reg(&self) -> $datatype {
             $reg()
         }
@@ -182,6 +296,50 @@ macro_rules! cpxmethod_rw {
         }
     }
 }
+/// Generates a method which calls the module-level function of the same name, with the same
+/// signature (minus `&self`), safety, and generics. A different function can be called with
+/// `=> path` after the signature, such as `fn tlb_clear() => tlb::clear`.
+macro_rules! cpxmethod_fn {
+    ($(#[$attr:meta])* unsafe fn $($rest:tt)*) => {
+        cpxmethod_fn!(@parse [$(#[$attr])*] [unsafe] $($rest)*);
+    };
+    ($(#[$attr:meta])* fn $($rest:tt)*) => {
+        cpxmethod_fn!(@parse [$(#[$attr])*] [] $($rest)*);
+    };
+    
+    (@parse $attrs:tt $unsafe:tt $name:ident<const $c:ident: $ct:ty>($($arg:ident: $argty:ty),*) $(-> $ret:ty)?) => {
+        cpxmethod_fn!(@emit $attrs $unsafe $name [const $c: $ct] ($($arg: $argty),*) [$(-> $ret)?] [$name] [$name::<$c>]);
+    };
+    (@parse $attrs:tt $unsafe:tt $name:ident<$($gen:ident $(: $bound:path)?),*>($($arg:ident: $argty:ty),*) $(-> $ret:ty)?) => {
+        cpxmethod_fn!(@emit $attrs $unsafe $name [$($gen $(: $bound)?),*] ($($arg: $argty),*) [$(-> $ret)?] [$name] [$name]);
+    };
+    (@parse $attrs:tt $unsafe:tt $name:ident($($arg:ident: $argty:ty),*) $(-> $ret:ty)? => $func:path) => {
+        cpxmethod_fn!(@emit $attrs $unsafe $name [] ($($arg: $argty),*) [$(-> $ret)?] [$func] [$func]);
+    };
+    (@parse $attrs:tt $unsafe:tt $name:ident($($arg:ident: $argty:ty),*) $(-> $ret:ty)?) => {
+        cpxmethod_fn!(@emit $attrs $unsafe $name [] ($($arg: $argty),*) [$(-> $ret)?] [$name] [$name]);
+    };
+    
+    (@emit [$($attr:tt)*] [] $name:ident [$($gen:tt)*] ($($arg:ident: $argty:ty),*) [$($ret:tt)*] [$doc:path] [$($call:tt)*]) => {
+        #[doc = concat!("See [`", stringify!($doc), "()`].")]
+        $($attr)*
+        #[inline(always)]
+        pub fn $name<$($gen)*>(&self, $($arg: $argty),*) $($ret)* {
+            $($call)*($($arg),*)
+        }
+    };
+    (@emit [$($attr:tt)*] [unsafe] $name:ident [$($gen:tt)*] ($($arg:ident: $argty:ty),*) [$($ret:tt)*] [$doc:path] [$($call:tt)*]) => {
+        #[doc = concat!("See [`", stringify!($doc), "()`].")]
+        #[doc = ""]
+        #[doc = "# Safety"]
+        #[doc = concat!("See [`", stringify!($doc), "()`].")]
+        $($attr)*
+        #[inline(always)]
+        pub unsafe fn $name<$($gen)*>(&self, $($arg: $argty),*) $($ret)* {
+            $($call)*($($arg),*)
+        }
+    };
+}
 
 macro_rules! derive_tofrom_primitive {
     ($kind:ident, $prim:ident) => {
@@ -198,22 +356,122 @@ macro_rules! derive_tofrom_primitive {
     }
 }
 
+/// Implements `bytemuck::Pod` and `bytemuck::Zeroable` for a register value type, when the
+/// `bytemuck` feature is enabled.
+/// 
+/// Only use this for `#[repr(transparent)]` wrappers of a primitive integer, or `#[repr(C)]` unions
+/// whose fields all are such wrappers of the same size. Every bit pattern must be a valid value.
+macro_rules! impl_pod {
+    ($kind:ident) => {
+        #[cfg(feature = "bytemuck")]
+        // SAFETY: See the requirements above; checked at each use.
+        unsafe impl bytemuck::Zeroable for $kind {}
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::Pod for $kind {}
+    }
+}
+
+/// Implements safe conversions between a union register and its `Read`/`Write` bitfield halves.
+/// 
+/// Every field of these unions is a plain `u32` wrapper, so reinterpreting the value as any of them
+/// is always sound. This is checked at compile time, by asserting that the union and both halves
+/// are exactly 4 bytes.
+macro_rules! impl_union_reg {
+    ($union:ident) => {
+        paste::paste! {
+            const _: () = assert!(
+                core::mem::size_of::<$union>() == 4
+                    && core::mem::size_of::<[<$union Read>]>() == 4
+                    && core::mem::size_of::<[<$union Write>]>() == 4,
+                concat!(stringify!($union), " and its read/write halves must all be 4 bytes"),
+            );
+            
+            impl $union {
+                /// Returns the value decoded as the register's read half, without needing `unsafe`.
+                #[inline(always)]
+                pub fn decoded(self) -> [<$union Read>] {
+                    unsafe { self.read }
+                }
+            }
+            impl From<[<$union Read>]> for $union {
+                fn from(value: [<$union Read>]) -> Self {
+                    Self { read: value }
+                }
+            }
+            impl From<[<$union Write>]> for $union {
+                fn from(value: [<$union Write>]) -> Self {
+                    Self { write: value }
+                }
+            }
+            impl From<$union> for [<$union Read>] {
+                fn from(value: $union) -> Self {
+                    value.decoded()
+                }
+            }
+        }
+    }
+}
+
 pub mod ai;
 pub mod cp0;
 pub mod cp1;
+pub mod dma;
+pub mod interrupt;
 pub mod mi;
+pub mod monitor;
+pub mod panic_screen;
 pub mod pi;
+pub mod prelude;
+pub mod registry;
+pub mod ri;
 pub mod si;
+pub mod sp;
 pub mod vi;
 
+#[repr(transparent)]
 pub struct RW<T: Copy>(T);
 impl<T: Copy> RW<T> {
     /// Reads the value this struct represents from memory.
+    #[must_use]
     #[inline(always)]
     pub fn read(&self) -> T {
         unsafe { (&self.0 as *const T).read_volatile() }
     }
     
+    /// Reads the value this struct represents from memory, and returns it as raw bytes in native
+    /// byte order, regardless of the register's type.
+    /// 
+    /// `N` must be equal to the size of `T`, which for most registers is 4. Mismatched sizes fail
+    /// to compile.
+    #[must_use]
+    #[inline(always)]
+    pub fn read_bytes<const N: usize>(&self) -> [u8; N] {
+        to_bytes(self.read())
+    }
+    
+    /// Repeatedly reads the value this struct represents, until `pred` returns `true` for it. Returns
+    /// the value which satisfied the predicate.
+    /// 
+    /// ```no_run
+    /// use n64_pac::pi::PeripheralInterface;
+    /// 
+    /// let pi = unsafe { PeripheralInterface::new() };
+    /// let _ = pi.status.wait_until(|status| !status.decoded().dma_busy());
+    /// ```
+    #[inline(always)]
+    pub fn wait_until<F: Fn(T) -> bool>(&self, pred: F) -> T {
+        poll_until(|| self.read(), pred, None).unwrap_or_else(|_| unreachable!())
+    }
+    
+    /// Like [`wait_until()`][Self::wait_until()], but gives up once `max_cycles` CP0 Count cycles
+    /// have passed, returning [`Timeout`].
+    /// 
+    /// The register is always read at least once, so an already satisfied condition never times out.
+    #[inline(always)]
+    pub fn wait_until_timeout<F: Fn(T) -> bool>(&self, pred: F, max_cycles: u32) -> Result<T, Timeout> {
+        poll_until(|| self.read(), pred, Some(max_cycles))
+    }
+    
     /// Writes the provided value to the memory represented by this struct.
     /// 
     /// # Safety
@@ -224,6 +482,42 @@ impl<T: Copy> RW<T> {
         unsafe { (&self.0 as *const T as *mut T).write_volatile(data); }
     }
     
+    /// Writes the provided value to the memory represented by this struct, and waits for the write
+    /// to reach the device.
+    /// 
+    /// A `sync` instruction is issued after the write, and the register is then read back. As the
+    /// read can only complete after all previous writes have left the CPU, the device is guaranteed
+    /// to have seen the write once this function returns. This also acts as a compiler barrier.
+    /// 
+    /// The read back value is discarded, so this shouldn't be used on registers where reading has
+    /// side effects.
+    #[inline(always)]
+    pub fn write_with_barrier(&self, data: T) {
+        let ptr = &self.0 as *const T as *mut T;
+        unsafe {
+            ptr.write_volatile(data);
+            #[cfg(target_arch = "mips")]
+            core::arch::asm!("sync");
+            ptr.read_volatile();
+        }
+    }
+    
+    /// Executes the provided function on the default (usually zero) value, and writes the result to
+    /// the memory represented by this struct.
+    /// 
+    /// Unlike [`modify()`][Self::modify()], the register isn't read first, so every field not set by
+    /// `func` is written as its default. This suits registers which are set from scratch:
+    /// ```no_run
+    /// use n64_pac::vi::{AntiAliasMode, ColorDepth, VideoInterface};
+    /// 
+    /// let vi = unsafe { VideoInterface::new() };
+    /// vi.ctrl.write_with(|ctrl| ctrl.with_depth(ColorDepth::BPP16).with_aa_mode(AntiAliasMode::Enabled));
+    /// ```
+    #[inline(always)]
+    pub fn write_with<F: FnOnce(T) -> T>(&self, func: F) where T: Default {
+        self.write(func(T::default()));
+    }
+    
     /// Reads the value this struct represents from memory, executes the provided function, and
     /// writes the resulting value back to memory.
     /// 
@@ -235,17 +529,119 @@ impl<T: Copy> RW<T> {
         let ptr = &self.0 as *const T as *mut T;
         unsafe { ptr.write_volatile(func(ptr.read_volatile())); }
     }
+    
+    /// Reads the value this struct represents from memory, writes the result of the provided
+    /// function back, and returns the value from before the modification.
+    /// 
+    /// Unlike [`modify()`][Self::modify()], the read and write are performed with interrupts
+    /// disabled (see [`cp0::interrupt_free()`]), so no interrupt handler can access the register in
+    /// between. As the VR4300 is single core, this makes the update atomic. "Atomic" here means
+    /// interrupt-free, not lock-free; interrupts are delayed until `func` returns, so it should be
+    /// short. The RCP may still change the register in between, such as when status bits are set by
+    /// hardware.
+    #[inline(always)]
+    pub fn fetch_update<F: FnOnce(T) -> T>(&self, func: F) -> T {
+        let ptr = &self.0 as *const T as *mut T;
+        cp0::interrupt_free(|| unsafe {
+            let old = ptr.read_volatile();
+            ptr.write_volatile(func(old));
+            old
+        })
+    }
 }
 
+#[repr(transparent)]
 pub struct RO<T: Copy>(T);
 impl<T: Copy> RO<T> {
     /// Reads the value this struct represents from memory.
+    #[must_use]
     #[inline(always)]
     pub fn read(&self) -> T {
         unsafe { (&self.0 as *const T).read_volatile() }
     }
+    
+    /// Reads the value this struct represents from memory, and returns it as raw bytes in native
+    /// byte order, regardless of the register's type.
+    /// 
+    /// `N` must be equal to the size of `T`, which for most registers is 4. Mismatched sizes fail
+    /// to compile.
+    #[must_use]
+    #[inline(always)]
+    pub fn read_bytes<const N: usize>(&self) -> [u8; N] {
+        to_bytes(self.read())
+    }
+    
+    /// Repeatedly reads the value this struct represents, until `pred` returns `true` for it. Returns
+    /// the value which satisfied the predicate.
+    /// 
+    /// ```no_run
+    /// use n64_pac::mi::MipsInterface;
+    /// 
+    /// let mi = unsafe { MipsInterface::new() };
+    /// let _ = mi.interrupt.wait_until(|pending| pending.vi());
+    /// ```
+    #[inline(always)]
+    pub fn wait_until<F: Fn(T) -> bool>(&self, pred: F) -> T {
+        poll_until(|| self.read(), pred, None).unwrap_or_else(|_| unreachable!())
+    }
+    
+    /// Like [`wait_until()`][Self::wait_until()], but gives up once `max_cycles` CP0 Count cycles
+    /// have passed, returning [`Timeout`].
+    /// 
+    /// The register is always read at least once, so an already satisfied condition never times out.
+    #[inline(always)]
+    pub fn wait_until_timeout<F: Fn(T) -> bool>(&self, pred: F, max_cycles: u32) -> Result<T, Timeout> {
+        poll_until(|| self.read(), pred, Some(max_cycles))
+    }
+}
+
+/// Returned when a register didn't reach the expected state within the given number of cycles.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Timeout;
+
+/// Calls `read` until `pred` is satisfied, or `max_cycles` CP0 Count cycles have passed.
+#[inline(always)]
+fn poll_until<T: Copy>(read: impl Fn() -> T, pred: impl Fn(T) -> bool, max_cycles: Option<u32>) -> Result<T, Timeout> {
+    let start = max_cycles.map(|_| cp0::count());
+    loop {
+        let value = read();
+        if pred(value) {
+            return Ok(value);
+        }
+        if let (Some(start), Some(max_cycles)) = (start, max_cycles) {
+            if cp0::count().wrapping_sub(start) > max_cycles {
+                return Err(Timeout);
+            }
+        }
+        core::hint::spin_loop();
+    }
 }
 
+/// Compile-time check that `N` is the size of `T`, evaluated when [`to_bytes()`] is instantiated.
+struct SizeOf<T, const N: usize>(PhantomData<T>);
+impl<T, const N: usize> SizeOf<T, N> {
+    const MATCHES: () = assert!(N == core::mem::size_of::<T>(), "byte array length must match the register size");
+}
+
+/// Reinterprets `value` as its raw bytes in memory. Fails to compile if `N` isn't the size of `T`.
+#[inline(always)]
+fn to_bytes<T: Copy, const N: usize>(value: T) -> [u8; N] {
+    let () = SizeOf::<T, N>::MATCHES;
+    unsafe { core::mem::transmute_copy(&value) }
+}
+
+/// Stand-in for CPU instructions when building for a host other than the VR4300, such as for
+/// `cargo doc`, `cargo check`, or host tests of the bitfield types.
+/// 
+/// Takes the operands the instruction would have used, so they aren't reported as unused. Always panics.
+#[cfg(not(target_arch = "mips"))]
+#[cold]
+#[inline(never)]
+pub(crate) fn host_unsupported<A, T>(_operands: A) -> T {
+    unimplemented!("CP0/CP1 instructions are only available when targeting the VR4300")
+}
+
+#[repr(transparent)]
 pub struct WO<T: Copy>(T);
 impl<T: Copy> WO<T> {
     /// Writes the provided value to the memory represented by this struct.
@@ -255,6 +651,168 @@ impl<T: Copy> WO<T> {
     }
 }
 
+/// A contiguous array of identical registers, or identical blocks of registers.
+/// 
+/// Used for register blocks which repeat the same register (or group of registers), so they can be
+/// accessed by index (or iterated over) instead of being duplicated as separately named fields. For
+/// example the PI's two sets of domain timing registers are a `RegArray<pi::Domain, 2>`.
+#[repr(transparent)]
+pub struct RegArray<R, const N: usize>([R; N]);
+impl<R, const N: usize> RegArray<R, N> {
+    /// Returns the number of elements in the array.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        N
+    }
+    
+    /// Returns true if the array contains no elements.
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+    
+    /// Returns the element at `index`, or `None` if it's out of bounds.
+    #[must_use]
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&R> {
+        self.0.get(index)
+    }
+    
+    /// Returns an iterator over every element in the array.
+    #[inline(always)]
+    pub fn iter(&self) -> core::slice::Iter<'_, R> {
+        self.0.iter()
+    }
+}
+impl<T: Copy, const N: usize> RegArray<RW<T>, N> {
+    /// Reads the register at `index`.
+    /// 
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self, index: usize) -> T {
+        self.0[index].read()
+    }
+    
+    /// Writes the provided value to the register at `index`.
+    /// 
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    pub fn write(&self, index: usize, data: T) {
+        self.0[index].write(data)
+    }
+}
+impl<R, const N: usize> core::ops::Index<usize> for RegArray<R, N> {
+    type Output = R;
+    
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+impl<'a, R, const N: usize> IntoIterator for &'a RegArray<R, N> {
+    type Item = &'a R;
+    type IntoIter = core::slice::Iter<'a, R>;
+    
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A write-only register paired with a copy of the last value written to it.
+/// 
+/// Write-only registers can't be read back, so they can't be modified with a read-modify-write.
+/// `ShadowReg` keeps the last written value in memory instead, allowing [`ShadowReg::modify()`] to
+/// work as it does for [`RW`] registers.
+/// 
+/// The shadow is only accurate if every write to the register goes through it. It also can't know
+/// what the register held before the first write, so it starts with the value given to
+/// [`ShadowReg::new()`], which can be corrected with [`ShadowReg::set_initial()`].
+pub struct ShadowReg<T: Copy> {
+    reg: *mut T,
+    shadow: Cell<T>,
+}
+// SAFETY: The N64 has a single CPU core, so a shadow can only be accessed concurrently by an
+// interrupt handler, the same caveat which applies to `RW::modify()`.
+unsafe impl<T: Copy> Sync for ShadowReg<T> {}
+impl<T: Copy> ShadowReg<T> {
+    /// Creates a shadow for the write-only register at `reg`, assuming it currently holds `initial`.
+    /// 
+    /// # Safety
+    /// `reg` must point to a valid write-only register, and every other write to the register must
+    /// go through this shadow.
+    #[inline(always)]
+    pub const unsafe fn new(reg: *mut WO<T>, initial: T) -> Self {
+        Self { reg: reg as *mut T, shadow: Cell::new(initial) }
+    }
+    
+    /// Returns the last value written to the register.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        self.shadow.get()
+    }
+    
+    /// Writes the provided value to the register, and records it in the shadow.
+    #[inline(always)]
+    pub fn write(&self, data: T) {
+        unsafe { self.reg.write_volatile(data); }
+        self.shadow.set(data);
+    }
+    
+    /// Passes the last written value to the provided function, and writes the result to the register.
+    /// 
+    /// # Safety
+    /// Unsafe when interrupts are enabled, as they could interrupt between this function reading
+    /// the shadow, and writing the modified data back.
+    #[inline(always)]
+    pub fn modify<F: FnOnce(T) -> T>(&self, func: F) {
+        self.write(func(self.shadow.get()));
+    }
+    
+    /// Overwrites the shadow without writing to the register.
+    /// 
+    /// Used when the register's current value is known from elsewhere, such as after a reset.
+    #[inline(always)]
+    pub fn set_initial(&self, value: T) {
+        self.shadow.set(value);
+    }
+}
+
+/// Reads the value of any [`RW`] register.
+/// 
+/// Equivalent to [`RW::read()`], but usable as a function value in generic code.
+#[must_use]
+#[inline(always)]
+pub fn read<T: Copy>(reg: &RW<T>) -> T {
+    reg.read()
+}
+
+/// Writes a value to any [`RW`] register.
+/// 
+/// Equivalent to [`RW::write()`], but usable as a function value in generic code.
+#[inline(always)]
+pub fn write<T: Copy>(reg: &RW<T>, data: T) {
+    reg.write(data)
+}
+
+/// Reads the value of any [`RW`] register, passes it through `func`, and writes the result back.
+/// 
+/// Equivalent to [`RW::modify()`], but usable as a function value in generic code. For example, a
+/// save/restore helper can be written once and applied to registers from every interface.
+/// 
+/// # Safety
+/// See [`RW::modify()`].
+#[inline(always)]
+pub fn modify<T: Copy, F: FnOnce(T) -> T>(reg: &RW<T>, func: F) {
+    reg.modify(func)
+}
+
 static mut HARDWARE_TAKEN: bool = false;
 
 /// Represents all hardware abstractions.
@@ -268,28 +826,46 @@ static mut HARDWARE_TAKEN: bool = false;
 pub struct Hardware {
     pub cp0: Cp0,
     pub cp1: Cp1,
+    pub sp: SignalProcessor,
     pub mi: MipsInterface,
     pub vi: VideoInterface,
     pub ai: AudioInterface,
     pub pi: PeripheralInterface,
-    //pub ri: RdramInterface,
+    pub ri: RdramInterface,
     pub si: SerialInterface,
 }
 impl Hardware {
     /// Attempts to take a singleton instance of `Hardware` and return it.
     /// 
-    /// If `take()` has already been called, `None` will be returned.
+    /// If `take()` (or [`Hardware::steal()`]) has already been called, `None` will be returned. The
+    /// instance is never given back, even if it's dropped. Use [`Hardware::is_taken()`] to check
+    /// without taking.
+    /// 
+    /// The check and the update of the taken flag aren't atomic. `take()` is expected to be called
+    /// once during startup, before interrupts are enabled; calling it from both regular code and an
+    /// interrupt handler could hand out two instances.
     /// 
     /// If you need multiple instances, consider using [`Hardware::steal()`].
+    #[must_use]
     #[inline]
     pub fn take() -> Option<Self> {
-        if unsafe { HARDWARE_TAKEN } {
+        if Self::is_taken() {
             None
         } else {
             Some(unsafe { Self::steal() })
         }
     }
     
+    /// Returns `true` if the singleton has been taken by [`Hardware::take()`], or any instance has
+    /// been created by [`Hardware::steal()`].
+    /// 
+    /// Useful when diagnosing why `take()` returned `None`.
+    #[must_use]
+    #[inline]
+    pub fn is_taken() -> bool {
+        unsafe { HARDWARE_TAKEN }
+    }
+    
     /// Bypasses the singleton pattern, providing a new abstraction instance of the available hardware.
     /// 
     /// # Safety
@@ -309,12 +885,69 @@ impl Hardware {
         Self {
             cp0: Cp0::new(),
             cp1: Cp1::new(),
+            sp: SignalProcessor::new(),
             mi: MipsInterface::new(),
             vi: VideoInterface::new(),
             ai: AudioInterface::new(),
             pi: PeripheralInterface::new(),
-            //ri: RdramInterface::new(),
+            ri: RdramInterface::new(),
             si: SerialInterface::new(),
         }
     }
+    
+    /// Provides an instance of the available hardware to a panic or exception handler, regardless of
+    /// whether [`Hardware::take()`] has already been called.
+    /// 
+    /// This is the escape hatch for crash reporting: the code which panicked may be holding the
+    /// singleton, and will never give it back. Interrupts are disabled (Status.IE cleared) before
+    /// returning, so no interrupt handler can touch the hardware while the crash is being reported.
+    /// 
+    /// # Safety
+    /// Must only be used by code which won't return control to the rest of the program, such as a
+    /// `#[panic_handler]`. Any other use carries the same risks as [`Hardware::steal()`].
+    #[inline]
+    pub unsafe fn steal_for_panic() -> Self {
+        cp0::modify_status(|status| status.with_ie(false));
+        
+        Self::steal()
+    }
+    
+    /// Enables the RCP interrupts in `set`, all the way through to the CPU.
+    /// 
+    /// RCP interrupts are masked at two levels, and both must be open for an interrupt to be taken:
+    /// 1. `MI_MASK`: the bits in `set` are unmasked, leaving the others unchanged.
+    /// 2. CP0 Status: the RCP interrupt line (IP2, [`im_int0`][cp0::StatusReg::im_int0()]) is
+    ///    unmasked, and interrupts are enabled globally (IE).
+    /// 
+    /// The interface raising the interrupt must still be configured to do so, such as by setting
+    /// `VI_V_INTR` to a line within the frame for the VI interrupt. Taken interrupts go to the
+    /// general exception vector, which must dispatch them, for example using
+    /// [`interrupt_handlers!`].
+    pub fn enable_interrupt(&mut self, set: mi::InterruptSet) {
+        unsafe {
+            mi::enable_interrupts(set);
+            cp0::modify_status(|status| status.with_im_int0(true).with_ie(true));
+        }
+    }
+    
+    /// Masks the RCP interrupts in `set` at the MI level, leaving the others unchanged.
+    /// 
+    /// The CP0 side is left enabled, as other RCP interrupts and the timer interrupt may still
+    /// need it.
+    pub fn disable_interrupt(&mut self, set: mi::InterruptSet) {
+        unsafe { mi::disable_interrupts(set); }
+    }
+    
+    /// Prints the state of every readable memory mapped register to `out`, grouped by interface.
+    /// 
+    /// Each interface is printed from the same register table as its module's `hexdump()`
+    /// function, such as [`vi::hexdump()`].
+    pub fn dump_all(&self, out: &mut impl core::fmt::Write) -> core::fmt::Result {
+        for (interface, registers) in monitor::INTERFACES {
+            writeln!(out, "[{interface}]")?;
+            monitor::hexdump(registers, out)?;
+        }
+        
+        Ok(())
+    }
 }
\ No newline at end of file
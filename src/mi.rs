@@ -1,8 +1,9 @@
 //! RCP - MIPS Interface
 
-use core::ops::Deref;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Deref, Not};
+use core::ptr::addr_of_mut;
 use proc_bitfield::bitfield;
-use crate::{RO, RW};
+use crate::{ai, pi, si, sp, vi, RO, RW};
 
 /// A wrapper around a mutable reference to the MIPS Interface's memory mapped registers.
 /// 
@@ -52,7 +53,165 @@ regfn_ro!(MipsInterface, version, VERSION, VersionReg);
 regfn_ro!(MipsInterface, interrupt, INTERRUPT, InterruptReg);
 regfn_rw_union!(MipsInterface, mask, MASK, MaskReg);
 
+monitor_table!("MI", [
+    monitor_reg!(MipsInterface, mode, rw_union, ModeReg),
+    monitor_reg!(MipsInterface, version, ro, VersionReg),
+    monitor_reg!(MipsInterface, interrupt, ro, InterruptReg),
+    monitor_reg!(MipsInterface, mask, rw_union, MaskReg),
+]);
 
+/// Enables RDRAM init mode (also known as repeat mode), with the provided init length, using a single
+/// write to the `MI_MODE` register.
+/// 
+/// While init mode is enabled, each write to RDRAM is repeated to fill `length + 1` bytes. This is
+/// used by the RDRAM initialization sequence to broadcast register writes. `length` is limited to 7 bits.
+#[inline(always)]
+pub unsafe fn set_init_mode(length: u8) {
+    set_mode(ModeRegWrite(0).with_init_length(length & 0x7F).set_init_mode());
+}
+
+/// Disables RDRAM init mode (also known as repeat mode).
+#[inline(always)]
+pub unsafe fn clear_init_mode() {
+    set_mode(ModeRegWrite(0).clear_init_mode());
+}
+
+/// Changes the requested `MI_MODE` modes using a single write, leaving all others unchanged.
+/// 
+/// `None` leaves a mode unchanged. For `init`, `Some(0)` disables init mode (a length of 0 wouldn't
+/// repeat writes anyway), while `Some(length)` enables it with the provided init length. The
+/// current init length is preserved when init mode isn't being changed, as every write to
+/// `MI_MODE` also writes the init length.
+/// 
+/// See [`ModeRegWrite::from_test_modes()`] for the value that gets written.
+#[inline(always)]
+pub unsafe fn set_test_modes(init: Option<u8>, ebus: Option<bool>, rdram_reg: Option<bool>) {
+    set_mode(ModeRegWrite::from_test_modes(mode(), init, ebus, rdram_reg));
+}
+
+/// Unmasks every interrupt in the provided set, leaving all other masks unchanged.
+/// 
+/// The `MI_MASK` register uses separate set/clear bits, so this is a single write rather than a
+/// read-modify-write.
+#[inline(always)]
+pub unsafe fn enable_interrupts(set: InterruptSet) {
+    set_mask(MaskRegWrite(0).set_masks(set));
+}
+
+/// Masks every interrupt in the provided set, leaving all other masks unchanged.
+/// 
+/// The `MI_MASK` register uses separate set/clear bits, so this is a single write rather than a
+/// read-modify-write.
+#[inline(always)]
+pub unsafe fn disable_interrupts(set: InterruptSet) {
+    set_mask(MaskRegWrite(0).clear_masks(set));
+}
+
+/// Returns true if any RCP interrupt is pending, regardless of whether it's masked.
+#[must_use]
+#[inline(always)]
+pub fn any_pending() -> bool {
+    interrupt().any()
+}
+
+/// Returns true if any RCP interrupt is both pending and unmasked.
+/// 
+/// This is the condition which raises the CPU's RCP interrupt line (Cause.IP2). An exception handler
+/// can use this to early-out when IP2 was set, but no unmasked interrupt remains to be handled.
+#[must_use]
+#[inline(always)]
+pub fn any_unmasked_pending() -> bool {
+    !(interrupt().sources() & mask().sources()).is_empty()
+}
+
+/// Errors reported by [`reset_rcp()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RcpResetError {
+    /// The PI DMA engine couldn't be recovered. See [`pi::recover()`].
+    Pi(pi::PiError),
+    /// The SI stayed busy. See [`si::reset()`].
+    Si(si::SiError),
+}
+
+/// Brings the RCP's subsystems to a known idle state, for initialization, or recovery from a wedged
+/// RCP.
+/// 
+/// The MI has no register which resets the RCP; a hardware reset only comes from the PIF, when the
+/// console is powered on or the reset button is pressed. Instead, this resets each subsystem
+/// through its own registers, in this order:
+/// 1. Masks every RCP interrupt in `MI_MASK`.
+/// 2. SP: halts the RSP, waits for any SP DMA to finish, and clears the broke flag, single-step
+///    mode, interrupt-on-break, all eight signals, and the SP interrupt.
+/// 3. DP: clears the DP interrupt through `MI_MODE`, leaving the other modes unchanged.
+/// 4. PI: resets the DMA engine, aborting any transfer, and acknowledges the PI interrupt
+///    ([`pi::recover()`]).
+/// 5. SI: acknowledges the SI interrupt, and waits for it to become idle ([`si::reset()`]).
+/// 6. AI: disables DMA, and acknowledges the AI interrupt. Queued buffers are left in the FIFO.
+/// 7. VI: acknowledges the VI interrupt. The display configuration is left unchanged.
+/// 
+/// Not cleared: the RSP's PC, IMEM, and DMEM, the RDP's command buffer and pipeline (the RDP has no
+/// reset control), RDRAM and RI configuration, and CPU interrupt state in CP0. Interrupts stay
+/// masked afterwards; unmask the wanted ones with [`enable_interrupts()`].
+/// 
+/// Returns an error if the PI or SI couldn't be brought to idle, in which case only a console reset
+/// will recover it. The remaining steps are still performed.
+/// 
+/// # Safety
+/// Every in-flight RSP task, and PI, SI, or AI transfer is aborted or discarded. No other code may
+/// use the RCP while this is running, including interrupt handlers.
+pub unsafe fn reset_rcp() -> Result<(), RcpResetError> {
+    disable_interrupts(InterruptSet::all());
+    
+    sp::set_status(sp::StatusRegWrite(0).set_halt());
+    sp::wait_dma();
+    sp::set_status(sp::StatusRegWrite(0)
+        .clear_broke()
+        .clear_interrupt()
+        .clear_single_step()
+        .clear_interrupt_on_break()
+        .clear_signals()
+    );
+    
+    set_mode(ModeRegWrite::from_test_modes(mode(), None, None, None).clear_dp_interrupt());
+    
+    let pi_result = pi::recover().map_err(RcpResetError::Pi);
+    let si_result = si::reset().map_err(RcpResetError::Si);
+    
+    ai::set_dma_enable(false);
+    ai::set_status(ai::StatusReg(0));
+    
+    vi::acknowledge_interrupt();
+    
+    pi_result.and(si_result)
+}
+
+/// Value of [`VERSION_CACHE`] indicating `MI_VERSION` hasn't been read yet.
+const VERSION_NOT_CACHED: u32 = 0;
+
+/// Copy of `MI_VERSION`, filled in by the first call to [`cached_version()`].
+static mut VERSION_CACHE: u32 = VERSION_NOT_CACHED;
+
+/// Reads the `MI_VERSION` register once, returning the cached value on every later call.
+/// 
+/// The RCP revision can't change while the console is running, so caching it is always valid, and
+/// avoids an uncached MMIO read each time code branches on the hardware revision. Use [`version()`]
+/// to read the register directly.
+#[must_use]
+#[inline]
+pub fn cached_version() -> VersionReg {
+    let cache = addr_of_mut!(VERSION_CACHE);
+    let mut value = unsafe { cache.read_volatile() };
+    if value == VERSION_NOT_CACHED {
+        // Racing with an interrupt handler here is harmless; both would store the same value.
+        value = version().0;
+        unsafe { cache.write_volatile(value); }
+    }
+    
+    VersionReg(value)
+}
+
+
+#[must_use]
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub union ModeReg {
@@ -60,9 +219,13 @@ pub union ModeReg {
     pub read: ModeRegRead,
     pub write: ModeRegWrite,
 }
+impl_pod!(ModeReg);
+impl_union_reg!(ModeReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ModeRegRead(pub u32): Debug {
         pub init_length: u8 [ro] @ 0..=6,
         pub init_mode: bool [ro] @ 7,
@@ -70,9 +233,12 @@ bitfield! {
         pub rdram_register_mode: bool [ro] @ 9,
     }
 }
+impl_pod!(ModeRegRead);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ModeRegWrite(pub u32): Debug {
         pub init_length: u8 [wo] @ 0..=6,
         clear_init_mode: bool [wo] @ 7,
@@ -84,7 +250,44 @@ bitfield! {
         set_rdram_register_mode: bool [wo] @ 13,
     }
 }
+impl_pod!(ModeRegWrite);
 impl ModeRegWrite {
+    /// Builds a write which changes only the requested modes, using the clear or set bit of each.
+    /// 
+    /// `current` provides the init length to preserve when `init` is `None`. See
+    /// [`set_test_modes()`] for the meaning of each argument. The returned value is never
+    /// [contradictory][Self::is_contradictory()].
+    pub fn from_test_modes(current: ModeRegRead, init: Option<u8>, ebus: Option<bool>, rdram_reg: Option<bool>) -> Self {
+        let mut value = Self(0);
+        value = match init {
+            None => value.with_init_length(current.init_length()),
+            Some(0) => value.clear_init_mode(),
+            Some(length) => value.with_init_length(length & 0x7F).set_init_mode(),
+        };
+        value = match ebus {
+            None => value,
+            Some(false) => value.clear_ebus_test_mode(),
+            Some(true) => value.set_ebus_test_mode(),
+        };
+        value = match rdram_reg {
+            None => value,
+            Some(false) => value.clear_rdram_register_mode(),
+            Some(true) => value.set_rdram_register_mode(),
+        };
+        
+        debug_assert!(!value.is_contradictory());
+        value
+    }
+    
+    /// Returns true if both the clear and set bits of any mode are set, which has undefined results.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_contradictory(self) -> bool {
+        (self.0 & 0x0080 != 0 && self.0 & 0x0100 != 0) ||
+            (self.0 & 0x0200 != 0 && self.0 & 0x0400 != 0) ||
+            (self.0 & 0x1000 != 0 && self.0 & 0x2000 != 0)
+    }
+    
     #[inline(always)]
     pub fn clear_init_mode(self) -> Self {
         self.with_clear_init_mode(true)
@@ -124,7 +327,9 @@ impl ModeRegWrite {
 
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct VersionReg(pub u32): Debug {
         pub io_version: u8 [ro] @ 0..=7,
         pub rac_version: u8 [ro] @ 8..=15,
@@ -132,11 +337,14 @@ bitfield! {
         pub rsp_version: u8 [ro] @ 24..=31,
     }
 }
+impl_pod!(VersionReg);
 
 
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct InterruptReg(pub u32): Debug {
         pub sp: bool [ro] @ 0,
         pub si: bool [ro] @ 1,
@@ -146,9 +354,25 @@ bitfield! {
         pub dp: bool [ro] @ 5,
     }
 }
+impl_pod!(InterruptReg);
+impl InterruptReg {
+    /// Returns the pending interrupts as an [`InterruptSet`].
+    #[inline(always)]
+    pub fn sources(&self) -> InterruptSet {
+        InterruptSet::from_bits_truncate(self.0)
+    }
+    
+    /// Returns true if any interrupt is pending, regardless of whether it's masked.
+    #[must_use]
+    #[inline(always)]
+    pub fn any(&self) -> bool {
+        !self.sources().is_empty()
+    }
+}
 
 
 
+#[must_use]
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub union MaskReg {
@@ -156,9 +380,13 @@ pub union MaskReg {
     pub read: MaskRegRead,
     pub write: MaskRegWrite,
 }
+impl_pod!(MaskReg);
+impl_union_reg!(MaskReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct MaskRegRead(pub u32): Debug {
         pub sp_interrupt_mask: bool [ro] @ 0,
         pub si_interrupt_mask: bool [ro] @ 1,
@@ -168,9 +396,19 @@ bitfield! {
         pub dp_interrupt_mask: bool [ro] @ 5,
     }
 }
+impl_pod!(MaskRegRead);
+impl MaskRegRead {
+    /// Returns the unmasked interrupts as an [`InterruptSet`].
+    #[inline(always)]
+    pub fn sources(&self) -> InterruptSet {
+        InterruptSet::from_bits_truncate(self.0)
+    }
+}
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct MaskRegWrite(pub u32): Debug {
         clear_sp: bool [wo] @ 0,
         set_sp: bool [wo] @ 1,
@@ -186,6 +424,7 @@ bitfield! {
         set_dp: bool [wo] @ 11,
     }
 }
+impl_pod!(MaskRegWrite);
 impl MaskRegWrite {
     #[inline(always)]
     pub fn clear_sp_mask(self) -> Self { self.with_clear_sp(true) }
@@ -216,4 +455,174 @@ impl MaskRegWrite {
     pub fn clear_dp_mask(self) -> Self { self.with_clear_dp(true) }
     #[inline(always)]
     pub fn set_dp_mask(self) -> Self { self.with_set_dp(true) }
+    
+    /// Sets the mask bit of every interrupt in the provided set.
+    #[inline(always)]
+    pub fn set_masks(self, set: InterruptSet) -> Self {
+        let mut value = self.0;
+        for i in 0..6 {
+            if set.0 & (1 << i) != 0 {
+                value |= 1 << (i * 2 + 1);
+            }
+        }
+        Self(value)
+    }
+    
+    /// Clears the mask bit of every interrupt in the provided set.
+    #[inline(always)]
+    pub fn clear_masks(self, set: InterruptSet) -> Self {
+        let mut value = self.0;
+        for i in 0..6 {
+            if set.0 & (1 << i) != 0 {
+                value |= 1 << (i * 2);
+            }
+        }
+        Self(value)
+    }
+}
+
+
+
+/// A set of RCP interrupt sources.
+/// 
+/// Uses the same bit layout as the [`InterruptReg`] and [`MaskRegRead`] registers, and supports the
+/// usual set operators so masks can be composed naturally:
+/// ```
+/// use n64_pac::mi::InterruptSet;
+/// 
+/// let mut set = InterruptSet::VI | InterruptSet::AI;
+/// set.insert(InterruptSet::PI);
+/// set.remove(InterruptSet::AI);
+/// 
+/// assert!(set.contains(InterruptSet::VI | InterruptSet::PI));
+/// assert!(!set.contains(InterruptSet::AI));
+/// assert_eq!(!set, InterruptSet::SP | InterruptSet::SI | InterruptSet::AI | InterruptSet::DP);
+/// ```
+#[must_use]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[repr(transparent)]
+pub struct InterruptSet(u32);
+impl InterruptSet {
+    /// Signal Processor (RSP)
+    pub const SP: Self = Self(1 << 0);
+    /// Serial Interface
+    pub const SI: Self = Self(1 << 1);
+    /// Audio Interface
+    pub const AI: Self = Self(1 << 2);
+    /// Video Interface
+    pub const VI: Self = Self(1 << 3);
+    /// Peripheral Interface
+    pub const PI: Self = Self(1 << 4);
+    /// Display Processor (RDP)
+    pub const DP: Self = Self(1 << 5);
+    
+    /// Returns a set containing no interrupts.
+    #[inline(always)]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    
+    /// Returns a set containing every interrupt.
+    #[inline(always)]
+    pub const fn all() -> Self {
+        Self(0x3F)
+    }
+    
+    /// Returns the raw bits of this set.
+    #[inline(always)]
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+    
+    /// Creates a set from raw bits, discarding any bits that don't correspond to an interrupt.
+    #[inline(always)]
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::all().0)
+    }
+    
+    /// Returns `true` if this set contains no interrupts.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    
+    /// Returns `true` if every interrupt in `other` is also in this set.
+    #[inline(always)]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    
+    /// Returns `true` if at least one interrupt in `other` is also in this set.
+    #[inline(always)]
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+    
+    /// Adds every interrupt in `other` to this set.
+    #[inline(always)]
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+    
+    /// Removes every interrupt in `other` from this set.
+    #[inline(always)]
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+    
+    /// Toggles every interrupt in `other` within this set.
+    #[inline(always)]
+    pub fn toggle(&mut self, other: Self) {
+        self.0 ^= other.0;
+    }
+}
+impl BitOr for InterruptSet {
+    type Output = Self;
+    
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOrAssign for InterruptSet {
+    #[inline(always)]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+impl BitAnd for InterruptSet {
+    type Output = Self;
+    
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+impl BitAndAssign for InterruptSet {
+    #[inline(always)]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+impl BitXor for InterruptSet {
+    type Output = Self;
+    
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(self.0 ^ rhs.0)
+    }
+}
+impl BitXorAssign for InterruptSet {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+impl Not for InterruptSet {
+    type Output = Self;
+    
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self::from_bits_truncate(!self.0)
+    }
 }
@@ -0,0 +1,189 @@
+//! Interactive register monitor
+//! 
+//! [`RegisterMonitor`] parses simple text commands, performs the requested register access, and
+//! prints the result to any [`core::fmt::Write`] sink. How lines of input are received (SI, a
+//! debug cartridge's USB port, etc.) is left to the application.
+//! 
+//! Supported commands:
+//! - `r <interface> <register>` reads a register and prints its raw value, decoded if possible.
+//! - `w <interface> <register> <value>` writes a value to a register. The value may be decimal,
+//!   or hexadecimal with a `0x` prefix.
+//! 
+//! Interfaces and registers use the same names as this crate's modules and register functions.
+//! For example `r vi ctrl` or `w mi mask 0x3f`. Only memory mapped registers are supported.
+//! 
+//! `w vi v_current` acknowledges the VI interrupt through
+//! [`vi::acknowledge_interrupt()`][crate::vi::acknowledge_interrupt()], ignoring the value.
+
+use core::fmt::{self, Write};
+use crate::{ai, mi, pi, ri, si, sp, vi, RO, RW, WO};
+
+/// A memory mapped register known to the monitor, built by `monitor_reg!` from the register
+/// functions of its module.
+pub(crate) struct Register {
+    pub(crate) name: &'static str,
+    /// Returns the register's address, from its field of the module's register block.
+    pub(crate) addr: fn() -> usize,
+    /// Reads the raw value through the module's getter. `None` for write-only registers.
+    pub(crate) read: Option<fn() -> u32>,
+    /// Writes a raw value through the module's setter. `None` for read-only registers.
+    pub(crate) write: Option<unsafe fn(u32)>,
+    /// Reading changes the hardware's state, so the register is only read on request.
+    pub(crate) read_side_effects: bool,
+    /// Prints the fields of a raw value read from the register, if it has a typed representation.
+    pub(crate) decode: Option<fn(u32, &mut dyn Write) -> fmt::Result>,
+}
+
+/// Address of a memory mapped register wrapper, used by `monitor_reg!`.
+pub(crate) trait Mmio: Sized {
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+}
+impl<T: Copy> Mmio for RW<T> {}
+impl<T: Copy> Mmio for RO<T> {}
+impl<T: Copy> Mmio for WO<T> {}
+
+/// The register table of each interface module, by module name.
+pub(crate) static INTERFACES: &[(&str, &[Register])] = &[
+    ("sp", sp::REGISTERS),
+    ("mi", mi::REGISTERS),
+    ("vi", vi::REGISTERS),
+    ("ai", ai::REGISTERS),
+    ("pi", pi::REGISTERS),
+    ("ri", ri::REGISTERS),
+    ("si", si::REGISTERS),
+];
+
+/// Errors reported by [`RegisterMonitor::execute()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MonitorError {
+    /// The line was empty, or didn't start with `r` or `w`.
+    UnknownCommand,
+    /// The command had too few or too many arguments.
+    InvalidArguments,
+    /// No register with the given interface and name exists.
+    UnknownRegister,
+    /// The value to write isn't a valid decimal or `0x` prefixed hexadecimal `u32`.
+    InvalidValue,
+    /// Attempted to read a write-only register.
+    NotReadable,
+    /// Attempted to write a read-only register.
+    NotWritable,
+    /// Writing to the output sink failed.
+    Output,
+}
+impl From<fmt::Error> for MonitorError {
+    fn from(_: fmt::Error) -> Self {
+        Self::Output
+    }
+}
+
+/// Executes register peek/poke commands, printing results to the sink `W`.
+/// 
+/// See the [module][crate::monitor] documentation for the supported commands.
+pub struct RegisterMonitor<W: Write> {
+    out: W,
+}
+impl<W: Write> RegisterMonitor<W> {
+    /// Creates a new monitor which prints to `out`.
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+    
+    /// Consumes the monitor, returning the output sink.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+    
+    /// Parses and executes a single command line.
+    /// 
+    /// Reads print `<interface> <register> = <value>`, followed by the decoded fields if the
+    /// register has a typed representation. Writes print `<interface> <register> <- <value>`.
+    /// 
+    /// # Safety
+    /// Any memory mapped register can be written, with all the consequences that entails. Reading
+    /// some registers may also have side effects.
+    pub unsafe fn execute(&mut self, line: &str) -> Result<(), MonitorError> {
+        let mut args = line.split_whitespace();
+        let command = args.next().ok_or(MonitorError::UnknownCommand)?;
+        if command != "r" && command != "w" {
+            return Err(MonitorError::UnknownCommand);
+        }
+        
+        let interface = args.next().ok_or(MonitorError::InvalidArguments)?;
+        let name = args.next().ok_or(MonitorError::InvalidArguments)?;
+        let reg = find_register(interface, name).ok_or(MonitorError::UnknownRegister)?;
+        
+        if command == "r" {
+            if args.next().is_some() {
+                return Err(MonitorError::InvalidArguments);
+            }
+            let read = reg.read.ok_or(MonitorError::NotReadable)?;
+            
+            let value = read();
+            write!(self.out, "{} {} = {:#010X}", interface, reg.name, value)?;
+            if let Some(decode) = reg.decode {
+                self.out.write_char(' ')?;
+                decode(value, &mut self.out)?;
+            }
+            self.out.write_char('\n')?;
+        } else {
+            let value = args.next().ok_or(MonitorError::InvalidArguments)?;
+            if args.next().is_some() {
+                return Err(MonitorError::InvalidArguments);
+            }
+            let write = reg.write.ok_or(MonitorError::NotWritable)?;
+            
+            let value = parse_value(value).ok_or(MonitorError::InvalidValue)?;
+            write(value);
+            writeln!(self.out, "{} {} <- {:#010X}", interface, reg.name, value)?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// Finds the memory mapped register named `name` within `interface`.
+pub(crate) fn find_register(interface: &str, name: &str) -> Option<&'static Register> {
+    let (_, registers) = INTERFACES.iter().find(|(iface, _)| *iface == interface)?;
+    registers.iter().find(|reg| reg.name == name)
+}
+
+/// Prints every readable register of `registers`, one per line, as its offset from the first
+/// register, name, raw value, and decoded fields (if it has a typed representation).
+/// 
+/// Registers with read side effects (such as `SP_SEMAPHORE`, which is acquired by reading it) are
+/// printed as skipped, without reading them.
+/// 
+/// Used by the `hexdump()` function of each interface module.
+pub(crate) fn hexdump(registers: &[Register], out: &mut dyn Write) -> fmt::Result {
+    let Some(first) = registers.first() else { return Ok(()) };
+    let base = (first.addr)();
+    for reg in registers {
+        let Some(read) = reg.read else { continue };
+        let offset = (reg.addr)() - base;
+        if reg.read_side_effects {
+            writeln!(out, "{:#07X}  {:<14} (skipped, read has side effects)", offset, reg.name)?;
+            continue;
+        }
+        
+        let value = read();
+        write!(out, "{:#07X}  {:<14} {:#010X}", offset, reg.name, value)?;
+        if let Some(decode) = reg.decode {
+            out.write_str("  ")?;
+            decode(value, out)?;
+        }
+        out.write_char('\n')?;
+    }
+    
+    Ok(())
+}
+
+/// Parses a decimal, or `0x` prefixed hexadecimal, `u32`.
+fn parse_value(text: &str) -> Option<u32> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
@@ -0,0 +1,129 @@
+//! Panic screen, for debugging on hardware where no other output is available.
+//! 
+//! [`show()`] disables interrupts, draws the most recent exception state onto a red screen, and
+//! halts the CPU. Enabling the `panic-dump` feature installs it as the `#[panic_handler]`.
+//! Applications which provide their own panic handler should leave the feature disabled, and may
+//! call [`show()`] from their handler instead.
+//! 
+//! Each value is drawn on its own labeled row, in the following order:
+//! 1. `EPC`, `CAUSE`, `STATUS`, and `BADVADDR`, in hexadecimal
+//! 2. `LINE` and `FILE` of the panic, if known
+//! 3. The panic message and location, as formatted by [`PanicInfo`]'s `Display` implementation,
+//!    wrapped to the width of the screen
+
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::ptr::addr_of_mut;
+use crate::{cp0, Hardware};
+use crate::vi::{self, text, ColorDepth, Framebuffer, VideoMode};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+
+/// Red, in RGBA5551 format.
+const BACKGROUND: u16 = vi::rgba16(0xFF, 0, 0, true);
+/// White, in RGBA5551 format.
+const FOREGROUND: u16 = vi::rgba16(0xFF, 0xFF, 0xFF, true);
+
+/// Left and right margin, in pixels.
+const MARGIN: u16 = 16;
+/// X position of the values, after the labels.
+const VALUE_X: u16 = MARGIN + 9 * text::GLYPH_WIDTH;
+/// Distance between rows, in pixels.
+const ROW_HEIGHT: u16 = 12;
+
+#[repr(C, align(64))]
+struct PanicBuffer([u16; WIDTH * HEIGHT]);
+static mut PANIC_BUFFER: PanicBuffer = PanicBuffer([0; WIDTH * HEIGHT]);
+
+/// Returns the video mode selected by the IPL3, based on the TV type it stored at `0x80000300`.
+fn boot_video_mode() -> VideoMode {
+    match unsafe { (0x80000300 as *const u32).read_volatile() } {
+        0 => VideoMode::Pal,
+        2 => VideoMode::Mpal,
+        _ => VideoMode::Ntsc,
+    }
+}
+
+/// Draws formatted text at a moving position, wrapping at the right margin.
+struct TextCursor<'a> {
+    fb: &'a Framebuffer,
+    left: u16,
+    x: u16,
+    y: u16,
+}
+impl TextCursor<'_> {
+    fn new(fb: &Framebuffer, x: u16, y: u16) -> TextCursor<'_> {
+        TextCursor { fb, left: x, x, y }
+    }
+}
+impl Write for TextCursor<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' || self.x + text::GLYPH_WIDTH > WIDTH as u16 - MARGIN {
+                self.x = self.left;
+                self.y = self.y.saturating_add(text::GLYPH_HEIGHT);
+            }
+            if c != '\n' {
+                text::draw_char(self.fb, self.x, self.y, FOREGROUND as u32, c);
+                self.x += text::GLYPH_WIDTH;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Displays the panic screen and halts.
+/// 
+/// The VI is reconfigured to display a framebuffer reserved for the panic screen. Any other
+/// hardware state is left untouched.
+pub fn show(info: &PanicInfo) -> ! {
+    unsafe {
+        // Disables interrupts; the panicking code may still hold the `Hardware` singleton
+        Hardware::steal_for_panic();
+        let exception = cp0::exception_info();
+        
+        // Uncached, so the VI sees every write without needing to flush the data cache
+        let buffer = ((addr_of_mut!(PANIC_BUFFER) as u32) | 0xA0000000) as *mut u16;
+        for i in 0..(WIDTH * HEIGHT) {
+            buffer.add(i).write_volatile(BACKGROUND);
+        }
+        
+        if let Ok(fb) = Framebuffer::new(buffer as *mut u8, WIDTH as u16, HEIGHT as u16, ColorDepth::BPP16) {
+            let color = FOREGROUND as u32;
+            let row_y = |row: u16| MARGIN + row * ROW_HEIGHT;
+            for (row, label) in ["EPC", "CAUSE", "STATUS", "BADVADDR", "LINE", "FILE"].iter().enumerate() {
+                text::draw_text(&fb, MARGIN, row_y(row as u16), color, label);
+            }
+            
+            text::draw_hex_u64(&fb, VALUE_X, row_y(0), color, exception.epc.0);
+            text::draw_hex_u32(&fb, VALUE_X, row_y(1), color, exception.cause.0);
+            text::draw_hex_u32(&fb, VALUE_X, row_y(2), color, exception.status.0);
+            text::draw_hex_u64(&fb, VALUE_X, row_y(3), color, exception.badvaddr.0);
+            
+            // Drawing can't fail, so the results are ignored
+            let mut cursor = TextCursor::new(&fb, VALUE_X, row_y(4));
+            if let Some(location) = info.location() {
+                let _ = write!(cursor, "{}", location.line());
+                cursor = TextCursor::new(&fb, VALUE_X, row_y(5));
+                let _ = cursor.write_str(location.file());
+            }
+            // The message continues below the file name, however many lines it wrapped onto
+            cursor.left = MARGIN;
+            let _ = write!(cursor, "\n\n{}", info);
+            
+            vi::setup(&fb, boot_video_mode());
+        }
+    }
+    
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(feature = "panic-dump")]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    show(info)
+}
@@ -1,8 +1,13 @@
 //! RCP - Peripheral Interface
 
+use core::marker::PhantomData;
 use core::ops::Deref;
+use core::ptr::addr_of_mut;
 use proc_bitfield::bitfield;
-use crate::RW;
+use crate::{mi, RegArray, RW};
+use crate::dma::{Busy, Completion, Idle};
+
+pub mod romfs;
 
 /// A wrapper around a mutable reference to the Peripheral Interface's memory mapped registers.
 /// 
@@ -18,14 +23,8 @@ pub struct RegisterBlock {
     pub rd_len: RW<u32>,
     pub wr_len: RW<u32>,
     pub status: RW<StatusReg>,
-    pub dom1_lat: RW<u32>,
-    pub dom1_pwd: RW<u32>,
-    pub dom1_pgs: RW<u32>,
-    pub dom1_rls: RW<u32>,
-    pub dom2_lat: RW<u32>,
-    pub dom2_pwd: RW<u32>,
-    pub dom2_pgs: RW<u32>,
-    pub dom2_rls: RW<u32>,
+    /// Bus timing of PI domain 1 (`domains[0]`) and domain 2 (`domains[1]`).
+    pub domains: RegArray<Domain, 2>,
 }
 impl PeripheralInterface {
     /// Creates a new wrapped mutable reference to the Peripheral Interface's memory mapped registers, starting at `0xA4600000`.
@@ -46,6 +45,15 @@ impl PeripheralInterface {
     pub unsafe fn new() -> Self { Self {
         r: &mut *(0xA4600000 as *mut RegisterBlock)
     }}
+    
+    /// Borrows the PI's DMA engine as an [`Idle`] type-state token.
+    /// 
+    /// The PI must not have a transfer in progress when this is called. See [`Dma`] for details.
+    #[inline(always)]
+    pub fn dma(&mut self) -> Dma<'_, Idle> {
+        debug_assert!(is_idle(), "PI DMA borrowed while a transfer is in progress");
+        Dma { _pi: PhantomData, _state: PhantomData }
+    }
 }
 impl Deref for PeripheralInterface {
     type Target = RegisterBlock;
@@ -56,8 +64,758 @@ impl Deref for PeripheralInterface {
     }
 }
 
+/// Bus timing registers of a single PI domain.
+/// 
+/// Domain 1 covers cartridge ROM and the 64DD IPL ROM, while domain 2 covers cartridge SRAM/FlashRAM
+/// and the 64DD registers. Each register holds its value minus one, in PI clock cycles.
+#[repr(C)]
+pub struct Domain {
+    /// Cycles between the address and the first read (8 bits)
+    pub lat: RW<u32>,
+    /// Length of the read/write strobe (8 bits)
+    pub pwd: RW<u32>,
+    /// Page size, where pages are `2^(pgs + 2)` bytes (4 bits)
+    pub pgs: RW<u32>,
+    /// Cycles between consecutive accesses (2 bits)
+    pub rls: RW<u32>,
+}
+impl Domain {
+    /// Programs all four timing registers of this domain. Values are masked to their register's width.
+    #[inline(always)]
+    pub fn configure(&self, latency: u8, pulse_width: u8, page_size: u8, release: u8) {
+        self.lat.write(latency as u32);
+        self.pwd.write(pulse_width as u32);
+        self.pgs.write((page_size & 0xF) as u32);
+        self.rls.write((release & 0x3) as u32);
+    }
+}
+
+macro_rules! regfn_domain {
+    ($dom:ident, $index:literal, $dom_name:ident) => {
+        regfn_domain!($dom, $index, $dom_name, lat, LAT);
+        regfn_domain!($dom, $index, $dom_name, pwd, PWD);
+        regfn_domain!($dom, $index, $dom_name, pgs, PGS);
+        regfn_domain!($dom, $index, $dom_name, rls, RLS);
+    };
+    ($dom:ident, $index:literal, $dom_name:ident, $reg:ident, $reg_name:ident) => {
+        paste::paste! {
+            #[doc = concat!("Creates a temporary pointer to the [`PeripheralInterface`], and reads data from its ", stringify!($dom_name), "_", stringify!($reg_name), " register.")]
+            #[must_use]
+            #[inline(always)]
+            pub fn [<$dom _ $reg>]() -> u32 {
+                unsafe { PeripheralInterface::new().domains[$index].$reg.read() }
+            }
+            
+            #[doc = concat!("Creates a temporary pointer to the [`PeripheralInterface`], and writes data to its ", stringify!($dom_name), "_", stringify!($reg_name), " register.")]
+            #[inline(always)]
+            pub unsafe fn [<set_ $dom _ $reg>](data: u32) {
+                PeripheralInterface::new().domains[$index].$reg.write(data);
+            }
+            
+            #[doc = concat!("Creates a temporary pointer to the [`PeripheralInterface`], reads data from its ", stringify!($dom_name), "_", stringify!($reg_name), " register, modifies the data, then finally writes back into the register.")]
+            #[inline(always)]
+            pub unsafe fn [<modify_ $dom _ $reg>]<F: FnOnce(u32) -> u32>(func: F) {
+                PeripheralInterface::new().domains[$index].$reg.modify(func);
+            }
+        }
+    };
+}
+
+regfn_rw!(PeripheralInterface, dram_addr, DRAM_ADDR, u32);
+regfn_rw!(PeripheralInterface, cart_addr, CART_ADDR, u32);
+regfn_rw!(PeripheralInterface, rd_len, RD_LEN, u32);
+regfn_rw!(PeripheralInterface, wr_len, WR_LEN, u32);
 regfn_rw_union!(PeripheralInterface, status, STATUS, StatusReg);
+regfn_domain!(dom1, 0, DOM1);
+regfn_domain!(dom2, 1, DOM2);
+
+monitor_table!("PI", [
+    monitor_reg!(PeripheralInterface, dram_addr, rw),
+    monitor_reg!(PeripheralInterface, cart_addr, rw),
+    monitor_reg!(PeripheralInterface, rd_len, rw),
+    monitor_reg!(PeripheralInterface, wr_len, rw),
+    monitor_reg!(PeripheralInterface, status, rw_union, StatusReg),
+    monitor_reg!(PeripheralInterface, dom1_lat, [domains[0].lat], rw),
+    monitor_reg!(PeripheralInterface, dom1_pwd, [domains[0].pwd], rw),
+    monitor_reg!(PeripheralInterface, dom1_pgs, [domains[0].pgs], rw),
+    monitor_reg!(PeripheralInterface, dom1_rls, [domains[0].rls], rw),
+    monitor_reg!(PeripheralInterface, dom2_lat, [domains[1].lat], rw),
+    monitor_reg!(PeripheralInterface, dom2_pwd, [domains[1].pwd], rw),
+    monitor_reg!(PeripheralInterface, dom2_pgs, [domains[1].pgs], rw),
+    monitor_reg!(PeripheralInterface, dom2_rls, [domains[1].rls], rw),
+]);
+
+/// Programs the bus timing of PI domain 1 (cartridge ROM and the 64DD IPL ROM).
+/// 
+/// - `latency` is written to `PI_BSD_DOM1_LAT` (8 bits): cycles between the address and the first read
+/// - `pulse_width` is written to `PI_BSD_DOM1_PWD` (8 bits): length of the read/write strobe
+/// - `page_size` is written to `PI_BSD_DOM1_PGS` (4 bits): pages are `2^(page_size + 2)` bytes
+/// - `release` is written to `PI_BSD_DOM1_RLS` (2 bits): cycles between consecutive accesses
+/// 
+/// Each register holds the value minus one, in PI clock cycles, as written by the IPL.
+#[inline(always)]
+pub unsafe fn configure_dom1(latency: u8, pulse_width: u8, page_size: u8, release: u8) {
+    configure_domain(0, latency, pulse_width, page_size, release);
+}
+
+/// Programs the bus timing of PI domain `index + 1`, as described by [`configure_dom1()`].
+/// 
+/// # Panics
+/// Panics if `index` isn't 0 or 1.
+#[inline(always)]
+pub unsafe fn configure_domain(index: usize, latency: u8, pulse_width: u8, page_size: u8, release: u8) {
+    PeripheralInterface::new().domains[index].configure(latency, pulse_width, page_size, release);
+}
+
+/// Bus timing of a PI domain, written by [`configure()`].
+/// 
+/// Each value is the number of PI clock cycles minus one, as described by [`configure_dom1()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DomainConfig {
+    /// Written to `PI_BSD_DOMx_LAT` (8 bits).
+    pub latency: u8,
+    /// Written to `PI_BSD_DOMx_PWD` (8 bits).
+    pub pulse_width: u8,
+    /// Written to `PI_BSD_DOMx_PGS` (4 bits).
+    pub page_size: u8,
+    /// Written to `PI_BSD_DOMx_RLS` (2 bits).
+    pub release: u8,
+}
+impl DomainConfig {
+    /// The slowest possible timing, which any device can be accessed with.
+    pub const SLOWEST: Self = Self { latency: 0xFF, pulse_width: 0xFF, page_size: 0xF, release: 0x3 };
+    
+    /// Checks that every value fits in its register.
+    pub fn validate(&self) -> Result<(), PiError> {
+        if self.page_size > 0xF || self.release > 0x3 {
+            Err(PiError::InvalidConfig)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Validates `config`, waits for any PI DMA to finish, then programs the bus timing of PI domain
+/// `domain + 1`.
+/// 
+/// Changing the timing while a DMA is using the domain corrupts the transfer, so the registers are
+/// only written once the PI is idle. Returns [`PiError::InvalidConfig`] if `domain` isn't 0 or 1,
+/// or a value doesn't fit in its register, in which case nothing is written.
+/// 
+/// # Safety
+/// No PI access (DMA or direct) may be started until this returns.
+pub unsafe fn configure(domain: usize, config: &DomainConfig) -> Result<(), PiError> {
+    if domain > 1 {
+        return Err(PiError::InvalidConfig);
+    }
+    config.validate()?;
+    
+    wait_dma();
+    configure_domain(domain, config.latency, config.pulse_width, config.page_size, config.release);
+    
+    Ok(())
+}
+
+/// Reads the first word of cartridge ROM, and programs the domain 1 timing registers from it, the
+/// same way the IPL does at boot.
+/// 
+/// The word is `0x80371240` for most cartridges, and maps to the registers as follows:
+/// 
+/// | Bits   | Example | Register           |
+/// |:------:|:-------:|:-------------------|
+/// | 31..24 | `0x80`  | (unused)           |
+/// | 23..20 | `0x3`   | `PI_BSD_DOM1_RLS`  |
+/// | 19..16 | `0x7`   | `PI_BSD_DOM1_PGS`  |
+/// | 15..8  | `0x12`  | `PI_BSD_DOM1_PWD`  |
+/// | 7..0   | `0x40`  | `PI_BSD_DOM1_LAT`  |
+/// 
+/// The word is read with the slowest possible timing, so it's read correctly regardless of the
+/// current settings. Returns the word that was read. See also [`RomHeader`], which decodes the same
+/// fields.
+/// 
+/// # Safety
+/// Waits for any PI DMA in progress to finish; no other PI access may happen until this returns.
+pub unsafe fn configure_dom1_from_header() -> u32 {
+    wait_dma();
+    configure_dom1(0xFF, 0xFF, 0xF, 0x3);
+    
+    let word = ((CartRegion::Rom.base() | 0xA0000000) as *const u32).read_volatile();
+    let [_, rls_pgs, pwd, lat] = word.to_be_bytes();
+    configure_dom1(lat, pwd, rls_pgs & 0xF, rls_pgs >> 4);
+    
+    word
+}
+
+/// Maximum number of bytes which can be transferred by a single PI DMA.
+/// 
+/// The length registers hold `length - 1` in 24 bits.
+pub const DMA_MAX_LEN: u32 = 0x01000000;
+
+/// Known regions of the PI's cartridge address space.
+/// 
+/// PI DMAs to or from an address outside of these regions don't reach any device, so they
+/// silently transfer nothing useful.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CartRegion {
+    /// Domain 2, address 1: 64DD registers, `0x05000000..0x06000000`
+    Dd64Registers,
+    /// Domain 1, address 1: 64DD IPL ROM, `0x06000000..0x08000000`
+    Dd64IplRom,
+    /// Domain 2, address 2: cartridge SRAM or FlashRAM, `0x08000000..0x10000000`
+    Sram,
+    /// Domain 1, address 2: cartridge ROM, `0x10000000..0x1FC00000`
+    Rom,
+    /// Domain 1, address 3: unused by retail cartridges, `0x1FD00000..0x80000000`
+    Domain1Address3,
+}
+impl CartRegion {
+    /// Every region, in address order.
+    pub const ALL: [CartRegion; 5] = [
+        Self::Dd64Registers,
+        Self::Dd64IplRom,
+        Self::Sram,
+        Self::Rom,
+        Self::Domain1Address3,
+    ];
+    
+    /// Returns the first address of the region.
+    #[must_use]
+    pub const fn base(self) -> u32 {
+        match self {
+            Self::Dd64Registers => 0x05000000,
+            Self::Dd64IplRom => 0x06000000,
+            Self::Sram => 0x08000000,
+            Self::Rom => 0x10000000,
+            Self::Domain1Address3 => 0x1FD00000,
+        }
+    }
+    
+    /// Returns the address immediately after the end of the region.
+    #[must_use]
+    pub const fn end(self) -> u32 {
+        match self {
+            Self::Dd64Registers => 0x06000000,
+            Self::Dd64IplRom => 0x08000000,
+            Self::Sram => 0x10000000,
+            Self::Rom => 0x1FC00000,
+            Self::Domain1Address3 => 0x80000000,
+        }
+    }
+}
+
+/// Classifies a PI cartridge address by the region it falls in.
+/// 
+/// Returns [`PiError::InvalidCartAddr`] if the address isn't in any known region, such as when an
+/// RDRAM address was passed by mistake. The address must be a PI bus address, not a KSEG0/KSEG1
+/// virtual address.
+pub fn validate_cart_addr(addr: u32) -> Result<CartRegion, PiError> {
+    CartRegion::ALL.into_iter()
+        .find(|region| (region.base()..region.end()).contains(&addr))
+        .ok_or(PiError::InvalidCartAddr)
+}
+
+/// Starts a DMA transfer of `len` bytes from cartridge space into RDRAM, without waiting for it to finish.
+/// 
+/// `dram` is the RDRAM address (8-byte aligned), and `cart` is the cartridge space address (2-byte
+/// aligned). Both may be given as physical, KSEG0, or KSEG1 addresses. `len` must be between 1 and
+/// [`DMA_MAX_LEN`].
+/// 
+/// The data cache is not updated by DMA transfers, so any cached copy of the destination must be
+/// invalidated before being read.
+#[inline(always)]
+pub unsafe fn start_dma_read(dram: u32, cart: u32, len: u32) {
+    debug_assert!(validate_cart_addr(cart & 0x1FFFFFFF).is_ok(), "cartridge address outside any known PI region");
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_cart_addr(cart & 0x1FFFFFFF);
+    addr_of_mut!(DMA_TO_CART).write(false);
+    set_wr_len(len - 1);
+}
+
+/// Starts a DMA transfer of `len` bytes from RDRAM into cartridge space, without waiting for it to finish.
+/// 
+/// `dram` is the RDRAM address (8-byte aligned), and `cart` is the cartridge space address (2-byte
+/// aligned). Both may be given as physical, KSEG0, or KSEG1 addresses. `len` must be between 1 and
+/// [`DMA_MAX_LEN`].
+/// 
+/// The data cache is not read by DMA transfers, so any cached copy of the source must be written
+/// back beforehand.
+#[inline(always)]
+pub unsafe fn start_dma_write(dram: u32, cart: u32, len: u32) {
+    debug_assert!(validate_cart_addr(cart & 0x1FFFFFFF).is_ok(), "cartridge address outside any known PI region");
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_cart_addr(cart & 0x1FFFFFFF);
+    addr_of_mut!(DMA_TO_CART).write(true);
+    set_rd_len(len - 1);
+}
+
+/// Direction of the last DMA started by [`start_dma_read()`] or [`start_dma_write()`], used to pick
+/// the length register read by [`bytes_remaining()`].
+static mut DMA_TO_CART: bool = false;
+
+/// Returns the number of bytes the current PI DMA transfer has left to copy, or 0 if the PI is idle.
+/// 
+/// The hardware decrements the active length register (`PI_WR_LEN` for reads from cartridge space,
+/// `PI_RD_LEN` for writes) as the transfer progresses, so this can be polled as a live progress
+/// indicator. It's updated once per internal block of up to 128 bytes, rather than per byte.
+/// 
+/// The direction is remembered by [`start_dma_read()`] and [`start_dma_write()`], so transfers
+/// started by writing the registers directly may read the wrong register.
+#[must_use]
+pub fn bytes_remaining() -> u32 {
+    if !status().dma_busy() {
+        return 0;
+    }
+    
+    let len = if unsafe { addr_of_mut!(DMA_TO_CART).read() } { rd_len() } else { wr_len() };
+    (len & 0x00FFFFFF) + 1
+}
+
+/// Callback of the transfer started by [`start_dma_read_notify()`] or [`start_dma_write_notify()`].
+static DMA_COMPLETION: Completion = Completion::new();
+
+/// Starts a DMA transfer from cartridge space into RDRAM, and returns immediately. `on_done` is
+/// called by [`on_dma_complete()`] once the transfer finishes.
+/// 
+/// The PI interrupt is unmasked in `MI_MASK`. The CPU side (Status.IE and IP2) must be enabled too,
+/// such as by [`Hardware::enable_interrupt()`][crate::Hardware::enable_interrupt()], and the
+/// general exception handler must route the PI interrupt to [`on_dma_complete()`]:
+/// 
+/// ```ignore
+/// n64_pac::interrupt_handlers! {
+///     fn handle_exception;
+///     pi => n64_pac::pi::on_dma_complete,
+/// }
+/// ```
+/// 
+/// `on_done` runs inside the interrupt handler, so it should be short, such as setting a flag. See
+/// [`start_dma_read()`] for the requirements of the other parameters.
+pub unsafe fn start_dma_read_notify(dram: u32, cart: u32, len: u32, on_done: fn()) {
+    DMA_COMPLETION.set(on_done);
+    mi::enable_interrupts(mi::InterruptSet::PI);
+    start_dma_read(dram, cart, len);
+}
+
+/// Starts a DMA transfer from RDRAM into cartridge space, and returns immediately. `on_done` is
+/// called by [`on_dma_complete()`] once the transfer finishes.
+/// 
+/// See [`start_dma_read_notify()`] for how the interrupt must be set up, and [`start_dma_write()`]
+/// for the requirements of the other parameters.
+pub unsafe fn start_dma_write_notify(dram: u32, cart: u32, len: u32, on_done: fn()) {
+    DMA_COMPLETION.set(on_done);
+    mi::enable_interrupts(mi::InterruptSet::PI);
+    start_dma_write(dram, cart, len);
+}
+
+/// PI interrupt handler which calls the callback of the transfer started by
+/// [`start_dma_read_notify()`] or [`start_dma_write_notify()`], if any. Each callback is only
+/// called once.
+/// 
+/// The interrupt must already have been acknowledged, as done by
+/// [`interrupt_handlers!`][crate::interrupt_handlers].
+pub fn on_dma_complete() {
+    DMA_COMPLETION.complete();
+}
+
+/// Blocks until the current PI DMA transfer has finished.
+#[inline(always)]
+pub fn wait_dma() {
+    let _ = unsafe { PeripheralInterface::new() }.status.wait_until(|status| !status.decoded().dma_busy());
+}
+
+/// Transfers `len` bytes from cartridge space into RDRAM, and waits for the transfer to finish.
+/// 
+/// See [`start_dma_read()`] for the requirements of each parameter.
+#[inline(always)]
+pub unsafe fn dma_read(dram: u32, cart: u32, len: u32) {
+    start_dma_read(dram, cart, len);
+    wait_dma();
+}
+
+/// Transfers `len` bytes from RDRAM into cartridge space, and waits for the transfer to finish.
+/// 
+/// See [`start_dma_write()`] for the requirements of each parameter.
+#[inline(always)]
+pub unsafe fn dma_write(dram: u32, cart: u32, len: u32) {
+    start_dma_write(dram, cart, len);
+    wait_dma();
+}
+
+/// Acknowledges a pending PI interrupt, by writing the `clear_interrupt` bit of `PI_STATUS`.
+/// 
+/// The PI interrupt is raised (in both `PI_STATUS` and `MI_INTERRUPT`) whenever a DMA transfer
+/// finishes. A PI interrupt handler should check `mi::interrupt().pi()`, handle the completed
+/// transfer, then call this function.
+#[inline(always)]
+pub unsafe fn acknowledge_interrupt() {
+    set_status(StatusRegWrite(0).clear_interrupt());
+}
+
+/// Number of CP0 Count cycles (roughly 1ms) [`recover()`] waits for the DMA engine to become idle.
+const RECOVER_TIMEOUT: u32 = 46875;
+
+/// Recovers the DMA engine after a DMA error, so that the next transfer doesn't inherit the error state.
+/// 
+/// Resetting the DMA engine alone isn't enough; this performs the complete sequence:
+/// 1. Resets the DMA engine, aborting any transfer in progress.
+/// 2. Waits for the `dma_busy` flag to clear.
+/// 3. Acknowledges the PI interrupt.
+/// 4. Verifies the `dma_error` flag has cleared.
+/// 
+/// Returns [`PiError::Timeout`] if the engine stays busy, or [`PiError::DmaError`] if the error
+/// flag remains set.
+pub unsafe fn recover() -> Result<(), PiError> {
+    set_status(StatusRegWrite(0).reset_dma());
+    
+    PeripheralInterface::new().status
+        .wait_until_timeout(|status| !status.decoded().dma_busy(), RECOVER_TIMEOUT)
+        .map_err(|_| PiError::Timeout)?;
+    
+    acknowledge_interrupt();
+    
+    if status().dma_error() {
+        Err(PiError::DmaError)
+    } else {
+        Ok(())
+    }
+}
+
+/// Transfers any number of bytes from cartridge space into RDRAM, splitting the transfer into
+/// multiple DMAs, and waiting for each to finish.
+/// 
+/// Each DMA is at most [`DMA_MAX_LEN`] bytes, and never crosses the end of a [`CartRegion`]. The PI
+/// applies the bus timing of the domain containing a DMA's start address to the whole transfer, so
+/// a DMA running from one region into the next (such as from SRAM into ROM) would access the
+/// second region with the wrong timing, returning corrupted data.
+/// 
+/// See [`start_dma_read()`] for the alignment requirements. Does nothing if `len` is 0.
+pub unsafe fn dma_read_large(dram: u32, cart: u32, len: u32) {
+    let mut offset = 0;
+    while offset < len {
+        let chunk = chunk_len(cart + offset, len - offset);
+        dma_read(dram + offset, cart + offset, chunk);
+        offset += chunk;
+    }
+}
+
+/// Transfers any number of bytes from RDRAM into cartridge space, splitting the transfer into
+/// multiple DMAs, and waiting for each to finish.
+/// 
+/// Transfers are split the same way as [`dma_read_large()`]. See [`start_dma_write()`] for the
+/// alignment requirements. Does nothing if `len` is 0.
+pub unsafe fn dma_write_large(dram: u32, cart: u32, len: u32) {
+    let mut offset = 0;
+    while offset < len {
+        let chunk = chunk_len(cart + offset, len - offset);
+        dma_write(dram + offset, cart + offset, chunk);
+        offset += chunk;
+    }
+}
+
+/// Returns the length of the next DMA of a large transfer at `cart`, with `remaining` bytes left:
+/// at most [`DMA_MAX_LEN`], and stopping at the end of `cart`'s region.
+fn chunk_len(cart: u32, remaining: u32) -> u32 {
+    let len = remaining.min(DMA_MAX_LEN);
+    match validate_cart_addr(cart & 0x1FFFFFFF) {
+        Ok(region) => len.min(region.end() - (cart & 0x1FFFFFFF)),
+        Err(_) => len,
+    }
+}
+
+/// Returns `true` if the PI has no DMA transfer in progress.
+/// 
+/// Checking this before starting a transfer is the simplest way to avoid overlapping DMAs. For a
+/// compile-time guarantee, see [`Dma`].
+#[must_use]
+#[inline(always)]
+pub fn is_idle() -> bool {
+    !status().dma_busy()
+}
+
+/// Type-state token for the PI's DMA engine, obtained from [`PeripheralInterface::dma()`].
+/// 
+/// A `Dma<Idle>` can start a single transfer, which consumes it and returns a `Dma<Busy>`. The idle
+/// token is only returned once the transfer has finished, so a second transfer can't be started
+/// while the first is still in flight. See the [`dma`][crate::dma] module for an example.
+#[must_use]
+pub struct Dma<'a, S> {
+    _pi: PhantomData<&'a mut PeripheralInterface>,
+    _state: PhantomData<S>,
+}
+impl<'a> Dma<'a, Idle> {
+    /// Starts a DMA transfer from cartridge space into RDRAM.
+    /// 
+    /// # Safety
+    /// See [`start_dma_read()`].
+    #[inline(always)]
+    pub unsafe fn start_read(self, dram: u32, cart: u32, len: u32) -> Dma<'a, Busy> {
+        start_dma_read(dram, cart, len);
+        Dma { _pi: PhantomData, _state: PhantomData }
+    }
+    
+    /// Starts a DMA transfer from RDRAM into cartridge space.
+    /// 
+    /// # Safety
+    /// See [`start_dma_write()`].
+    #[inline(always)]
+    pub unsafe fn start_write(self, dram: u32, cart: u32, len: u32) -> Dma<'a, Busy> {
+        start_dma_write(dram, cart, len);
+        Dma { _pi: PhantomData, _state: PhantomData }
+    }
+}
+impl<'a> Dma<'a, Busy> {
+    /// Returns the number of bytes the transfer has left to copy. See [`bytes_remaining()`].
+    #[must_use]
+    #[inline(always)]
+    pub fn bytes_remaining(&self) -> u32 {
+        bytes_remaining()
+    }
+    
+    /// Returns the idle token if the transfer has finished, or the busy token back otherwise.
+    #[inline(always)]
+    pub fn poll(self) -> Result<Dma<'a, Idle>, Self> {
+        if is_idle() {
+            Ok(Dma { _pi: PhantomData, _state: PhantomData })
+        } else {
+            Err(self)
+        }
+    }
+    
+    /// Blocks until the transfer has finished, then returns the idle token.
+    #[inline(always)]
+    pub fn wait(self) -> Dma<'a, Idle> {
+        wait_dma();
+        Dma { _pi: PhantomData, _state: PhantomData }
+    }
+}
+
+/// Size of the bounce buffer used by [`CartReader`].
+const CART_READER_BUFFER_LEN: usize = 512;
+
+/// Bounce buffer which [`CartReader`] DMAs into, before copying into the caller's buffer.
+/// 
+/// Aligned to the data cache line size, and only ever accessed through an uncached pointer, so the
+/// cache never holds stale (or dirty) copies of it.
+#[repr(C, align(16))]
+struct CartReaderBuffer([u8; CART_READER_BUFFER_LEN]);
+static mut CART_READER_BUFFER: CartReaderBuffer = CartReaderBuffer([0; CART_READER_BUFFER_LEN]);
+
+/// A streaming reader over a range of cartridge space, such as an asset stored in ROM.
+/// 
+/// Each call to [`CartReader::read()`] DMAs the next chunk of data through an internal bounce
+/// buffer, so the destination can have any alignment, and any length. The cartridge address
+/// alignment required by the PI is also handled internally.
+/// 
+/// Data isn't DMAed straight into the caller's buffer, even when it's suitably aligned. The PI
+/// writes RDRAM behind the data cache, so the buffer's cache lines would have to be invalidated
+/// first, and any dirty lines it shares with neighbouring data written back, which this crate
+/// doesn't provide cache operations for. The bounce buffer is only accessed uncached, which avoids
+/// this, at the cost of one copy. Because it's shared, constructing a reader is `unsafe`. For large
+/// transfers into cache-managed buffers, use [`dma_read_large()`] directly.
+/// 
+/// With the `embedded-io` feature enabled, this implements `embedded_io::Read` and
+/// `embedded_io::Seek`, allowing it to be used with generic deserialization code. Seeking before
+/// the start of the range fails with `ErrorKind::InvalidInput`.
+pub struct CartReader {
+    base: u32,
+    len: usize,
+    pos: usize,
+}
+impl CartReader {
+    /// Creates a reader over `len` bytes of cartridge space, starting at the PI address `base`.
+    /// 
+    /// # Safety
+    /// All readers share a single bounce buffer, so reads from different readers must not overlap,
+    /// such as when one is used in an interrupt handler. No other PI DMA may be in progress while
+    /// reading.
+    pub unsafe fn new(base: u32, len: usize) -> Self {
+        Self { base, len, pos: 0 }
+    }
+    
+    /// Returns the current position, relative to the start of the range.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    
+    /// Returns the number of bytes left to read.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.len - self.pos
+    }
+    
+    /// Moves the current position, clamping it to the end of the range.
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos.min(self.len);
+    }
+    
+    /// Reads up to `buf.len()` bytes from the current position, returning the number of bytes read.
+    /// 
+    /// At most one bounce buffer's worth of data is transferred per call. Returns 0 only when the
+    /// end of the range has been reached, `buf` is empty, or the current position lies beyond the
+    /// end of the 32-bit PI address space.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let Some(cart) = u32::try_from(self.pos).ok().and_then(|pos| self.base.checked_add(pos)) else {
+            return 0;
+        };
+        let aligned = cart & !1;
+        let skip = (cart - aligned) as usize;
+        let count = buf.len().min(self.remaining()).min(CART_READER_BUFFER_LEN - skip);
+        if count == 0 {
+            return 0;
+        }
+        
+        let bounce = ((unsafe { addr_of_mut!(CART_READER_BUFFER) } as u32) | 0xA0000000) as *mut u8;
+        // PI DMA lengths are kept even; reading one byte past the range is harmless
+        let dma_len = ((skip + count + 1) & !1) as u32;
+        unsafe {
+            dma_read(bounce as u32, aligned, dma_len);
+            for (i, byte) in buf[..count].iter_mut().enumerate() {
+                *byte = bounce.add(skip + i).read_volatile();
+            }
+        }
+        
+        self.pos += count;
+        count
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::ErrorType for CartReader {
+    type Error = embedded_io::ErrorKind;
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Read for CartReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(CartReader::read(self, buf))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Seek for CartReader {
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+        let target = match pos {
+            embedded_io::SeekFrom::Start(offset) => i64::try_from(offset).ok(),
+            embedded_io::SeekFrom::End(offset) => i64::try_from(self.len).ok().and_then(|len| len.checked_add(offset)),
+            embedded_io::SeekFrom::Current(offset) => i64::try_from(self.pos).ok().and_then(|pos| pos.checked_add(offset)),
+        };
+        // Seeking before the start is an error, while seeking past the end clamps to the end
+        let target = target.and_then(|target| usize::try_from(target).ok()).ok_or(embedded_io::ErrorKind::InvalidInput)?;
+        self.set_position(target);
+        
+        Ok(self.pos as u64)
+    }
+}
+
+/// Size of the cartridge ROM header, in bytes.
+pub const ROM_HEADER_LEN: usize = 0x40;
+
+/// The standard header found at the start of cartridge ROM.
+/// 
+/// See [`read_rom_header()`], or [`RomHeader::parse()`] for a header which has already been read.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RomHeader {
+    /// First byte of the header, `0x80` for a ROM in native (big endian) byte order.
+    pub byte_order: u8,
+    /// Domain 1 release duration, used by the IPL to configure `PI_BSD_DOM1_RLS`.
+    pub dom1_release: u8,
+    /// Domain 1 page size, used by the IPL to configure `PI_BSD_DOM1_PGS`.
+    pub dom1_page_size: u8,
+    /// Domain 1 pulse width, used by the IPL to configure `PI_BSD_DOM1_PWD`.
+    pub dom1_pulse_width: u8,
+    /// Domain 1 latency, used by the IPL to configure `PI_BSD_DOM1_LAT`.
+    pub dom1_latency: u8,
+    /// CPU clock rate override. Zero uses the default rate.
+    pub clock_rate: u32,
+    /// Address in RDRAM which the IPL3 boot code jumps to, once the program has been loaded.
+    pub entry_point: u32,
+    /// Version of the SDK (libultra) the program was built with.
+    pub release: u32,
+    /// The two checksum words verified by the IPL3 boot code.
+    pub checksum: [u32; 2],
+    /// Game title, in ASCII (or Shift-JIS), padded with spaces. See [`RomHeader::title_str()`].
+    pub title: [u8; 20],
+    /// Four character game code: category, two character ID, and region. See [`RomHeader::region()`].
+    pub game_code: [u8; 4],
+    /// Revision of the game, starting at 0.
+    pub version: u8,
+}
+impl RomHeader {
+    /// Parses a header from the first [`ROM_HEADER_LEN`] bytes of ROM, in native byte order.
+    pub fn parse(bytes: &[u8; ROM_HEADER_LEN]) -> Self {
+        let word = |offset: usize| u32::from_be_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let mut title = [0; 20];
+        title.copy_from_slice(&bytes[0x20..0x34]);
+        let mut game_code = [0; 4];
+        game_code.copy_from_slice(&bytes[0x3B..0x3F]);
+        
+        Self {
+            byte_order: bytes[0x00],
+            dom1_release: bytes[0x01] >> 4,
+            dom1_page_size: bytes[0x01] & 0xF,
+            dom1_pulse_width: bytes[0x02],
+            dom1_latency: bytes[0x03],
+            clock_rate: word(0x04),
+            entry_point: word(0x08),
+            release: word(0x0C),
+            checksum: [word(0x10), word(0x14)],
+            title,
+            game_code,
+            version: bytes[0x3F],
+        }
+    }
+    
+    /// Returns `true` if the header is in native (big endian) byte order.
+    /// 
+    /// ROM dumps are sometimes byte or half-word swapped, but a cartridge read through the PI
+    /// always returns native order. This is mainly useful for headers read from other sources.
+    #[must_use]
+    pub fn is_native_byte_order(&self) -> bool {
+        self.byte_order == 0x80
+    }
+    
+    /// Returns the game title with trailing spaces and nulls removed, or `None` if it isn't valid
+    /// UTF-8 (such as a Shift-JIS title).
+    #[must_use]
+    pub fn title_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.title).ok()
+            .map(|title| title.trim_end_matches([' ', '\0']))
+    }
+    
+    /// Returns the region character of the game code, such as `b'E'` (North America), `b'J'`
+    /// (Japan), or `b'P'` (Europe).
+    #[must_use]
+    pub fn region(&self) -> u8 {
+        self.game_code[3]
+    }
+}
+
+/// DMAs the first [`ROM_HEADER_LEN`] bytes of cartridge ROM, and parses them as a [`RomHeader`].
+/// 
+/// # Safety
+/// This uses a [`CartReader`], so the same requirements apply: no other PI DMA may be in progress,
+/// and no other `CartReader` may be reading at the same time.
+pub unsafe fn read_rom_header() -> RomHeader {
+    let mut bytes = [0; ROM_HEADER_LEN];
+    CartReader::new(CartRegion::Rom.base(), ROM_HEADER_LEN).read(&mut bytes);
+    
+    RomHeader::parse(&bytes)
+}
+
+/// Errors reported by PI helper functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PiError {
+    /// The `dma_error` flag is set in the `PI_STATUS` register.
+    DmaError,
+    /// The PI didn't become idle within the expected time.
+    Timeout,
+    /// The cartridge address isn't within any known [`CartRegion`].
+    InvalidCartAddr,
+    /// A [`DomainConfig`] value doesn't fit in its register, or the domain doesn't exist.
+    InvalidConfig,
+}
 
+#[must_use]
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub union StatusReg {
@@ -65,9 +823,13 @@ pub union StatusReg {
     pub read: StatusRegRead,
     pub write: StatusRegWrite,
 }
+impl_pod!(StatusReg);
+impl_union_reg!(StatusReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct StatusRegRead(pub u32): Debug {
         pub dma_busy: bool [ro] @ 0,
         pub io_busy: bool [ro] @ 1,
@@ -75,14 +837,18 @@ bitfield! {
         pub interrupt: bool [ro] @ 3,
     }
 }
+impl_pod!(StatusRegRead);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct StatusRegWrite(pub u32): Debug {
         clear_interrupt: bool [wo] @ 0,
         reset_dma: bool [wo] @ 1,
     }
 }
+impl_pod!(StatusRegWrite);
 impl StatusRegWrite {
     #[inline(always)]
     pub fn clear_interrupt(self) -> Self {
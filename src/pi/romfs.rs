@@ -0,0 +1,170 @@
+//! Named asset lookup in cartridge ROM
+//! 
+//! Assets are located through a table of contents (TOC) stored in ROM, where each entry holds the
+//! hash of an asset's name, and the offset and length of its data. The position and format of the
+//! TOC is described by a [`Layout`], so the layouts used by different build tools can be read.
+//! 
+//! [`Layout::new()`] describes a simple default format: a big endian `u32` entry count, followed
+//! by 12 byte entries of big endian `u32` name hash ([`fnv1a()`]), offset, and length. Offsets are
+//! relative to the start of ROM.
+//! 
+//! ```no_run
+//! use n64_pac::pi::romfs::{self, Layout};
+//! 
+//! unsafe {
+//!     romfs::init(Layout::new(0x10100000));
+//!     if let Some(mut reader) = romfs::open("sprites/player.bin") {
+//!         let mut buf = [0; 256];
+//!         let len = reader.read(&mut buf);
+//!     }
+//! }
+//! ```
+//! 
+//! Names are only compared by hash, so two assets whose names share a hash can't be told apart.
+
+use crate::pi::{CartReader, CartRegion};
+
+/// Maximum length of a TOC entry, in bytes.
+pub const MAX_ENTRY_LEN: usize = 32;
+
+/// How the number of entries in the TOC is determined.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EntryCount {
+    /// The TOC holds a fixed number of entries, starting at [`Layout::toc_addr`].
+    Fixed(u32),
+    /// The TOC starts with a big endian `u32` entry count, followed by the entries.
+    Prefixed,
+}
+
+/// Describes the position and format of a TOC in cartridge space.
+/// 
+/// All fields within an entry are big endian `u32`s, at the given byte offsets.
+#[derive(Copy, Clone)]
+pub struct Layout {
+    /// PI address of the start of the TOC.
+    pub toc_addr: u32,
+    /// Number of entries in the TOC.
+    pub count: EntryCount,
+    /// Length of each entry in bytes, at most [`MAX_ENTRY_LEN`].
+    pub entry_len: usize,
+    /// Offset of the name hash within an entry.
+    pub hash_offset: usize,
+    /// Offset of the data offset within an entry.
+    pub data_offset: usize,
+    /// Offset of the data length within an entry.
+    pub len_offset: usize,
+    /// PI address which data offsets are relative to.
+    pub data_base: u32,
+    /// Function used to hash names, which must match the one used to build the TOC.
+    pub hash: fn(&[u8]) -> u32,
+}
+impl Layout {
+    /// Creates the default layout, with a TOC at the PI address `toc_addr`.
+    /// 
+    /// See the [module][self] documentation for the format.
+    #[must_use]
+    pub const fn new(toc_addr: u32) -> Self {
+        Self {
+            toc_addr,
+            count: EntryCount::Prefixed,
+            entry_len: 12,
+            hash_offset: 0,
+            data_offset: 4,
+            len_offset: 8,
+            data_base: CartRegion::Rom.base(),
+            hash: fnv1a,
+        }
+    }
+    
+    /// Parses a single TOC entry, returning the name hash, and the PI address and length of its
+    /// data.
+    /// 
+    /// # Panics
+    /// Panics if `entry` is shorter than one of the fields.
+    #[must_use]
+    pub fn parse_entry(&self, entry: &[u8]) -> (u32, u32, u32) {
+        let word = |offset: usize| u32::from_be_bytes([entry[offset], entry[offset + 1], entry[offset + 2], entry[offset + 3]]);
+        
+        (word(self.hash_offset), self.data_base.wrapping_add(word(self.data_offset)), word(self.len_offset))
+    }
+    
+    /// Looks up the asset called `name`, returning a reader over its data.
+    /// 
+    /// The TOC is scanned linearly, reading one entry at a time.
+    /// 
+    /// # Safety
+    /// This uses a [`CartReader`], so the same requirements apply: no other PI DMA may be in
+    /// progress, and no other `CartReader` may be reading at the same time.
+    /// 
+    /// # Panics
+    /// Panics if [`Layout::entry_len`] is greater than [`MAX_ENTRY_LEN`].
+    pub unsafe fn open(&self, name: &str) -> Option<CartReader> {
+        assert!(self.entry_len <= MAX_ENTRY_LEN, "TOC entries can be at most {} bytes", MAX_ENTRY_LEN);
+        
+        let mut toc_addr = self.toc_addr;
+        let count = match self.count {
+            EntryCount::Fixed(count) => count,
+            EntryCount::Prefixed => {
+                let mut bytes = [0; 4];
+                read_exact(toc_addr, &mut bytes);
+                toc_addr += 4;
+                u32::from_be_bytes(bytes)
+            }
+        };
+        
+        let hash = (self.hash)(name.as_bytes());
+        let mut entry = [0; MAX_ENTRY_LEN];
+        let entry = &mut entry[..self.entry_len];
+        for i in 0..count {
+            read_exact(toc_addr + i * self.entry_len as u32, entry);
+            let (entry_hash, addr, len) = self.parse_entry(entry);
+            if entry_hash == hash {
+                return Some(CartReader::new(addr, len as usize));
+            }
+        }
+        
+        None
+    }
+}
+
+static mut LAYOUT: Option<Layout> = None;
+
+/// Sets the layout used by [`open()`].
+/// 
+/// # Safety
+/// Must not be called while [`open()`] is running, such as from an interrupt handler.
+pub unsafe fn init(layout: Layout) {
+    LAYOUT = Some(layout);
+}
+
+/// Looks up the asset called `name`, using the layout set by [`init()`]. Returns `None` if the
+/// asset doesn't exist, or [`init()`] hasn't been called.
+/// 
+/// # Safety
+/// See [`Layout::open()`].
+pub unsafe fn open(name: &str) -> Option<CartReader> {
+    LAYOUT.as_ref()?.open(name)
+}
+
+/// Computes the 32-bit FNV-1a hash of `bytes`, as used by the default [`Layout`].
+#[must_use]
+pub const fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash = 0x811C9DC5u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x01000193);
+        i += 1;
+    }
+    
+    hash
+}
+
+/// Fills `buf` from the PI address `addr`.
+unsafe fn read_exact(addr: u32, buf: &mut [u8]) {
+    let mut reader = CartReader::new(addr, buf.len());
+    let mut filled = 0;
+    while filled < buf.len() {
+        filled += reader.read(&mut buf[filled..]);
+    }
+}
@@ -0,0 +1,19 @@
+//! Commonly used types, for glob importing.
+//! 
+//! ```
+//! use n64_pac::prelude::*;
+//! ```
+//! 
+//! This only covers the types most programs need. Register bitfield types, and everything else,
+//! are imported from their own module.
+
+pub use crate::{Hardware, RO, RW, WO};
+pub use crate::ai::AudioInterface;
+pub use crate::cp0::{Cp0, Delay, Duration, ExceptionCode, ExceptionInfo, Instant};
+pub use crate::cp1::{Cp1, RoundingMode};
+pub use crate::mi::{InterruptSet, MipsInterface};
+pub use crate::pi::PeripheralInterface;
+pub use crate::ri::RdramInterface;
+pub use crate::si::SerialInterface;
+pub use crate::sp::SignalProcessor;
+pub use crate::vi::{ColorDepth, Framebuffer, VideoInterface, VideoMode};
@@ -0,0 +1,111 @@
+//! Register access by name at runtime
+//! 
+//! [`peek()`] and [`poke()`] look up a register from its module and register names, using the same
+//! names as this crate's modules and register functions (for example `peek("vi", "v_current")`).
+//! This allows debug consoles, trainers, and test scripts to access any register without knowing
+//! it at compile time. The [`monitor`][crate::monitor] provides a text command interface on top of
+//! the same memory mapped register table.
+//! 
+//! Supported modules:
+//! - `sp`, `mi`, `vi`, `ai`, `pi`, `ri`, `si`: memory mapped registers, as listed by each module's
+//!   `hexdump()`. Writing `vi`'s `v_current` calls
+//!   [`vi::acknowledge_interrupt()`][crate::vi::acknowledge_interrupt()], ignoring the value, so it
+//!   acknowledges the VI interrupt and applies any framebuffer queued by
+//!   [`vi::flip()`][crate::vi::flip()]. Reading it returns
+//!   [`vi::current_halfline()`][crate::vi::current_halfline()].
+//! - `cp0`: every CP0 register, by its [`Cp0Register::name()`] (case-insensitive, e.g. `count`).
+//! - `cp1`: the control registers `fcr0` and `fcr31`, and (read-only) the floating-point registers
+//!   `f0` to `f31`. Writing floating-point registers isn't supported, as the compiler may hold
+//!   values in them at any time.
+
+use crate::cp0::Cp0Register;
+use crate::cp1::Cp1Register;
+use crate::monitor::find_register;
+
+/// Errors reported by [`peek()`] and [`poke()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RegError {
+    /// No register with the given module and name exists.
+    UnknownRegister,
+    /// Attempted to read a write-only register.
+    NotReadable,
+    /// Attempted to write a read-only register.
+    NotWritable,
+    /// The value doesn't fit in the 32-bit register being written.
+    ValueTooLarge,
+    /// The register exists, but writing it by name isn't supported.
+    Unsupported,
+}
+
+/// Reads the register `reg` of `module`, zero-extending 32-bit registers.
+/// 
+/// Returns `None` if the register doesn't exist or can't be read. Registers whose reads have side
+/// effects (such as `SP_SEMAPHORE`, which is acquired by reading it) aren't read either, as with
+/// each module's `hexdump()`.
+/// 
+/// # Safety
+/// Registers are read through their module's getters, without any synchronisation with other code
+/// or interrupt handlers using the same registers.
+pub unsafe fn peek(module: &str, reg: &str) -> Option<u64> {
+    match module {
+        "cp0" => find_cp0(reg).map(Cp0Register::read),
+        "cp1" => find_cp1(reg).map(Cp1Register::read),
+        _ => {
+            let reg = find_register(module, reg)?;
+            if reg.read_side_effects {
+                return None;
+            }
+            
+            reg.read.map(|read| read() as u64)
+        }
+    }
+}
+
+/// Writes `value` to the register `reg` of `module`.
+/// 
+/// # Safety
+/// Any register can be written, with all the consequences that entails. See the `set_*` function of
+/// the register being written.
+pub unsafe fn poke(module: &str, reg: &str, value: u64) -> Result<(), RegError> {
+    match module {
+        "cp0" => {
+            let reg = find_cp0(reg).ok_or(RegError::UnknownRegister)?;
+            if !reg.is_writable() {
+                return Err(RegError::NotWritable);
+            }
+            if !reg.is_64bit() && value > u32::MAX as u64 {
+                return Err(RegError::ValueTooLarge);
+            }
+            
+            reg.write(value);
+        }
+        "cp1" => {
+            let reg = find_cp1(reg).ok_or(RegError::UnknownRegister)?;
+            let value = u32::try_from(value).map_err(|_| RegError::ValueTooLarge)?;
+            match reg {
+                Cp1Register::ControlStatus => crate::cp1::set_control_status(value.into()),
+                Cp1Register::ImplementationRevision => return Err(RegError::NotWritable),
+                Cp1Register::Fpr(_) => return Err(RegError::Unsupported),
+            }
+        }
+        _ => {
+            let reg = find_register(module, reg).ok_or(RegError::UnknownRegister)?;
+            let write = reg.write.ok_or(RegError::NotWritable)?;
+            let value = u32::try_from(value).map_err(|_| RegError::ValueTooLarge)?;
+            
+            write(value);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Finds a CP0 register by name, ignoring case.
+fn find_cp0(name: &str) -> Option<Cp0Register> {
+    Cp0Register::all().find(|reg| reg.name().eq_ignore_ascii_case(name))
+}
+
+/// Finds a CP1 register by name, ignoring case.
+fn find_cp1(name: &str) -> Option<Cp1Register> {
+    Cp1Register::all().find(|reg| reg.name().eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,144 @@
+//! RCP - RDRAM Interface
+//! 
+//! The RI registers are part of the delicate RDRAM initialization sequence, which is normally
+//! performed by the IPL3 during boot. Writing incorrect values can cause memory instability or
+//! corruption, which may not be immediately noticeable.
+
+use core::ops::Deref;
+use proc_bitfield::bitfield;
+use crate::RW;
+
+/// A wrapper around a mutable reference to the RDRAM Interface's memory mapped registers.
+/// 
+/// See [`RdramInterface::new()`] for usage details.
+pub struct RdramInterface {
+    r: &'static mut RegisterBlock,
+}
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub mode: RW<u32>,
+    pub config: RW<u32>,
+    pub current_load: RW<u32>,
+    pub select: RW<u32>,
+    pub refresh: RW<RefreshReg>,
+    pub latency: RW<LatencyReg>,
+    pub error: RW<u32>,
+    pub bank_status: RW<u32>,
+}
+impl RdramInterface {
+    /// Creates a new wrapped mutable reference to the RDRAM Interface's memory mapped registers, starting at `0xA4700000`.
+    /// 
+    /// Developers are recommended to use [`Hardware::take()`][crate::Hardware::take()] instead.
+    /// But for unrestricted, unsafe, access, this struct provides a method-based version to the
+    /// static functions available at the [module][crate::ri] level.
+    /// 
+    /// # Safety
+    /// This provides unrestricted access to memory mapped registers. Data races _could_ occur if writing
+    /// to a register in both regular code and inside interrupt handlers.
+    /// 
+    /// This is especially problematic if performing a read-modify-write operation; an interrupt
+    /// could trigger between reading a register, and writing a modified value back to the same
+    /// register. Thus anything written to that register inside the interrupt, would only apply for
+    /// a short moment before being overwritten.
+    #[inline(always)]
+    pub unsafe fn new() -> Self { Self {
+        r: &mut *(0xA4700000 as *mut RegisterBlock)
+    }}
+}
+impl Deref for RdramInterface {
+    type Target = RegisterBlock;
+    
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.r
+    }
+}
+
+regfn_rw!(RdramInterface, mode, MODE, u32);
+regfn_rw!(RdramInterface, config, CONFIG, u32);
+regfn_rw!(RdramInterface, current_load, CURRENT_LOAD, u32);
+regfn_rw!(RdramInterface, select, SELECT, u32);
+regfn_rw!(RdramInterface, refresh, REFRESH, RefreshReg,
+    /// Prefer the [`REFRESH_4MB`] and [`REFRESH_8MB`] presets. Incorrect refresh timings cause RDRAM
+    /// to lose data, resulting in memory instability.
+);
+regfn_rw!(RdramInterface, latency, LATENCY, LatencyReg);
+regfn_rw!(RdramInterface, error, ERROR, u32);
+regfn_rw!(RdramInterface, bank_status, BANK_STATUS, u32);
+
+monitor_table!("RI", [
+    monitor_reg!(RdramInterface, mode, rw),
+    monitor_reg!(RdramInterface, config, rw),
+    monitor_reg!(RdramInterface, current_load, rw),
+    monitor_reg!(RdramInterface, select, rw),
+    monitor_reg!(RdramInterface, refresh, rw, RefreshReg),
+    monitor_reg!(RdramInterface, latency, rw, LatencyReg),
+    monitor_reg!(RdramInterface, error, rw),
+    monitor_reg!(RdramInterface, bank_status, rw),
+]);
+
+/// Refresh configuration for a console with 4MB of RDRAM (two modules), as set up by the IPL3.
+pub const REFRESH_4MB: RefreshReg = RefreshReg(0x001E3634);
+
+/// Refresh configuration for a console with 8MB of RDRAM (four modules, using the Expansion Pak),
+/// as set up by the IPL3.
+pub const REFRESH_8MB: RefreshReg = RefreshReg(0x007E3634);
+
+/// Writes the RDRAM DMA latency, in cycles.
+/// 
+/// Returns [`RiError::InvalidLatency`] without writing anything if `cycles` doesn't fit in the
+/// register's 4 bits. Incorrect values cause memory instability.
+#[inline(always)]
+pub unsafe fn set_latency_cycles(cycles: u8) -> Result<(), RiError> {
+    if cycles > 0xF {
+        return Err(RiError::InvalidLatency);
+    }
+    
+    set_latency(LatencyReg(0).with_latency(cycles));
+    Ok(())
+}
+
+/// Errors reported by RI helper functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RiError {
+    /// The latency doesn't fit in the 4-bit `RI_LATENCY` register.
+    InvalidLatency,
+}
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct RefreshReg(pub u32): Debug {
+        /// Refresh delay for clean (unmodified) RDRAM rows
+        pub clean_delay: u8 @ 0..=7,
+        
+        /// Refresh delay for dirty (modified) RDRAM rows
+        pub dirty_delay: u8 @ 8..=15,
+        
+        /// Bank which will be refreshed next
+        pub bank: bool @ 16,
+        
+        /// Enables automatic refresh
+        pub enable: bool @ 17,
+        
+        /// Enables refresh optimization, skipping refresh cycles where possible
+        pub optimize: bool @ 18,
+        
+        /// Multi-bank selection, one bit per RDRAM module
+        pub multibank: u8 @ 19..=22,
+    }
+}
+impl_pod!(RefreshReg);
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct LatencyReg(pub u32): Debug {
+        /// DMA latency/overlap, in cycles
+        pub latency: u8 @ 0..=3,
+    }
+}
+impl_pod!(LatencyReg);
@@ -1,8 +1,14 @@
 //! RCP - Serial Interface
 
+use core::marker::PhantomData;
 use core::ops::Deref;
+use core::ptr::addr_of_mut;
 use proc_bitfield::bitfield;
-use crate::RW;
+use crate::{mi, RW};
+use crate::dma::{Busy, Completion, Idle};
+use crate::si::joybus::{ControllerState, DeviceType, MouseState};
+
+pub mod joybus;
 
 /// A wrapper around a mutable reference to the Serial Interface's memory mapped registers.
 /// 
@@ -41,6 +47,15 @@ impl SerialInterface {
     pub unsafe fn new() -> Self { Self {
         r: &mut *(0xA4800000 as *mut RegisterBlock)
     }}
+    
+    /// Borrows the SI's DMA engine as an [`Idle`] type-state token.
+    /// 
+    /// The SI must not have a transfer in progress when this is called. See [`Dma`] for details.
+    #[inline(always)]
+    pub fn dma(&mut self) -> Dma<'_, Idle> {
+        debug_assert!(is_idle(), "SI DMA borrowed while a transfer is in progress");
+        Dma { _si: PhantomData, _state: PhantomData }
+    }
 }
 impl Deref for SerialInterface {
     type Target = RegisterBlock;
@@ -58,9 +73,323 @@ regfn_rw!(SerialInterface, pif_ad_wr64b, PIF_AD_WR64B, u32);
 regfn_rw!(SerialInterface, pif_ad_rd4b, PIF_AD_RD4B, u32);
 regfn_rw!(SerialInterface, status, STATUS, StatusReg);
 
+monitor_table!("SI", [
+    monitor_reg!(SerialInterface, dram_addr, rw),
+    monitor_reg!(SerialInterface, pif_ad_rd64b, rw),
+    monitor_reg!(SerialInterface, pif_ad_wr4b, rw),
+    monitor_reg!(SerialInterface, pif_ad_wr64b, rw),
+    monitor_reg!(SerialInterface, pif_ad_rd4b, rw),
+    monitor_reg!(SerialInterface, status, rw, StatusReg),
+]);
+
+/// Physical address of the 64-byte PIF RAM.
+pub const PIF_RAM_ADDR: u32 = 0x1FC007C0;
+
+/// DMA buffer used to transfer command blocks to and from PIF RAM.
+/// 
+/// Aligned to the data cache line size, so that no other data shares its cache lines, and only
+/// ever accessed through an uncached pointer.
+#[repr(C, align(16))]
+struct PifBuffer([u8; 64]);
+static mut PIF_BUFFER: PifBuffer = PifBuffer([0; 64]);
+
+/// Number of CP0 Count cycles (roughly 10ms) to wait for an SI transfer before giving up.
+/// 
+/// A PIF transaction normally completes well within a millisecond, so this is only reached when
+/// the SI or PIF is stuck, such as due to a faulty accessory.
+pub const DMA_TIMEOUT: u32 = 468750;
+
+/// Blocks until the current SI DMA transfer has finished, then checks whether it failed.
+/// 
+/// Returns [`SiError::Timeout`] if the transfer doesn't finish within [`DMA_TIMEOUT`] cycles.
+/// 
+/// This doesn't acknowledge the SI interrupt raised by the transfer; see [`dma_to_pif()`] and
+/// [`dma_from_pif()`] for helpers which do.
+pub fn wait_dma() -> Result<(), SiError> {
+    wait_idle()?;
+    
+    if status().dma_error() {
+        Err(SiError::DmaError)
+    } else {
+        Ok(())
+    }
+}
+
+/// Callback of the transfer started by [`start_dma_to_pif_notify()`] or [`start_dma_from_pif_notify()`].
+static DMA_COMPLETION: Completion = Completion::new();
+
+/// Starts a 64-byte DMA transfer from RDRAM into PIF RAM, and returns immediately. `on_done` is
+/// called by [`on_dma_complete()`] once the transfer finishes.
+/// 
+/// The SI interrupt is unmasked in `MI_MASK`. The CPU side (Status.IE and IP2) must be enabled too,
+/// such as by [`Hardware::enable_interrupt()`][crate::Hardware::enable_interrupt()], and the
+/// general exception handler must route the SI interrupt to [`on_dma_complete()`], for example
+/// with `si => n64_pac::si::on_dma_complete` in [`interrupt_handlers!`][crate::interrupt_handlers].
+/// 
+/// `on_done` runs inside the interrupt handler, and should check [`StatusReg::dma_error()`]
+/// if the result matters. See [`dma_to_pif()`] for the requirements of the other parameters.
+pub unsafe fn start_dma_to_pif_notify(dram: u32, pif: u32, on_done: fn()) {
+    DMA_COMPLETION.set(on_done);
+    mi::enable_interrupts(mi::InterruptSet::SI);
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_pif_ad_wr64b(pif & 0x1FFFFFFF);
+}
+
+/// Starts a 64-byte DMA transfer from PIF RAM into RDRAM, and returns immediately. `on_done` is
+/// called by [`on_dma_complete()`] once the transfer finishes.
+/// 
+/// See [`start_dma_to_pif_notify()`] for how the interrupt must be set up, and [`dma_from_pif()`]
+/// for the requirements of the other parameters.
+pub unsafe fn start_dma_from_pif_notify(pif: u32, dram: u32, on_done: fn()) {
+    DMA_COMPLETION.set(on_done);
+    mi::enable_interrupts(mi::InterruptSet::SI);
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_pif_ad_rd64b(pif & 0x1FFFFFFF);
+}
+
+/// SI interrupt handler which calls the callback of the transfer started by
+/// [`start_dma_to_pif_notify()`] or [`start_dma_from_pif_notify()`], if any. Each callback is only
+/// called once.
+/// 
+/// The interrupt must already have been acknowledged, as done by
+/// [`interrupt_handlers!`][crate::interrupt_handlers].
+pub fn on_dma_complete() {
+    DMA_COMPLETION.complete();
+}
+
+/// Waits up to [`DMA_TIMEOUT`] cycles for the `dma_busy` and `io_busy` flags to clear.
+fn wait_idle() -> Result<(), SiError> {
+    unsafe { SerialInterface::new() }.status
+        .wait_until_timeout(|status| !status.dma_busy() && !status.io_busy(), DMA_TIMEOUT)
+        .map(|_| ())
+        .map_err(|_| SiError::Timeout)
+}
+
+/// Attempts to recover an SI which is stuck after a failed or timed out transfer.
+/// 
+/// The SI has no reset control, so this acknowledges any pending SI interrupt, then waits up to
+/// [`DMA_TIMEOUT`] cycles for the engine to become idle. Returns [`SiError::Timeout`] if it stays
+/// busy, in which case only a console reset will recover it. If it returns `Ok`, the next
+/// transaction can be attempted; rewriting the whole command block is recommended, as the contents
+/// of PIF RAM are unknown.
+/// 
+/// # Safety
+/// No other code may use the SI while this is running.
+pub unsafe fn reset() -> Result<(), SiError> {
+    set_status(StatusReg(0));
+    wait_idle()?;
+    set_status(StatusReg(0));
+    
+    Ok(())
+}
+
+/// Returns `true` if the SI has no DMA transfer in progress.
+#[must_use]
+#[inline(always)]
+pub fn is_idle() -> bool {
+    !status().dma_busy()
+}
+
+/// Type-state token for the SI's DMA engine, obtained from [`SerialInterface::dma()`].
+/// 
+/// Works the same way as [`pi::Dma`][crate::pi::Dma], except that finishing a transfer also
+/// acknowledges the SI interrupt, and reports whether the transfer failed.
+#[must_use]
+pub struct Dma<'a, S> {
+    _si: PhantomData<&'a mut SerialInterface>,
+    _state: PhantomData<S>,
+}
+impl<'a> Dma<'a, Idle> {
+    /// Starts a 64-byte DMA transfer from RDRAM into PIF RAM.
+    /// 
+    /// # Safety
+    /// See [`dma_to_pif()`].
+    #[inline(always)]
+    pub unsafe fn start_to_pif(self, dram: u32, pif: u32) -> Dma<'a, Busy> {
+        set_dram_addr(dram & 0x1FFFFFFF);
+        set_pif_ad_wr64b(pif & 0x1FFFFFFF);
+        Dma { _si: PhantomData, _state: PhantomData }
+    }
+    
+    /// Starts a 64-byte DMA transfer from PIF RAM into RDRAM.
+    /// 
+    /// # Safety
+    /// See [`dma_from_pif()`].
+    #[inline(always)]
+    pub unsafe fn start_from_pif(self, pif: u32, dram: u32) -> Dma<'a, Busy> {
+        set_dram_addr(dram & 0x1FFFFFFF);
+        set_pif_ad_rd64b(pif & 0x1FFFFFFF);
+        Dma { _si: PhantomData, _state: PhantomData }
+    }
+}
+impl<'a> Dma<'a, Busy> {
+    /// Returns the idle token and the transfer's result if it has finished, or the busy token back
+    /// otherwise.
+    #[inline(always)]
+    pub fn poll(self) -> Result<(Dma<'a, Idle>, Result<(), SiError>), Self> {
+        if is_idle() {
+            Ok(self.wait())
+        } else {
+            Err(self)
+        }
+    }
+    
+    /// Blocks until the transfer has finished, acknowledges the SI interrupt, then returns the idle
+    /// token along with the transfer's result.
+    #[inline(always)]
+    pub fn wait(self) -> (Dma<'a, Idle>, Result<(), SiError>) {
+        let result = wait_dma();
+        unsafe { set_status(StatusReg(0)); }
+        
+        (Dma { _si: PhantomData, _state: PhantomData }, result)
+    }
+}
+
+/// Transfers 64 bytes from RDRAM into PIF RAM, waits for the transfer to finish, and acknowledges
+/// the resulting SI interrupt.
+/// 
+/// `dram` is the RDRAM address (8-byte aligned), and `pif` is the PIF RAM address (usually
+/// [`PIF_RAM_ADDR`]). Both may be given as physical, KSEG0, or KSEG1 addresses.
+/// 
+/// The SI interrupt flag is mirrored in `SI_STATUS`, `MI_INTERRUPT`, and the RCP Interrupt Cause
+/// register. A single write to `SI_STATUS` clears all three, which this function does once the
+/// transfer has finished, whether or not it succeeded.
+/// 
+/// # Safety
+/// The data cache is not read by DMA transfers, so any cached copy of the source must be written
+/// back beforehand. No other SI transfer may be in progress.
+pub unsafe fn dma_to_pif(dram: u32, pif: u32) -> Result<(), SiError> {
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_pif_ad_wr64b(pif & 0x1FFFFFFF);
+    let result = wait_dma();
+    set_status(StatusReg(0));
+    
+    result
+}
+
+/// Transfers 64 bytes from PIF RAM into RDRAM, waits for the transfer to finish, and acknowledges
+/// the resulting SI interrupt.
+/// 
+/// See [`dma_to_pif()`] for the requirements of each parameter, and how the interrupt is cleared.
+/// 
+/// # Safety
+/// The data cache is not updated by DMA transfers, so any cached copy of the destination must be
+/// invalidated before being read. No other SI transfer may be in progress.
+pub unsafe fn dma_from_pif(pif: u32, dram: u32) -> Result<(), SiError> {
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_pif_ad_rd64b(pif & 0x1FFFFFFF);
+    let result = wait_dma();
+    set_status(StatusReg(0));
+    
+    result
+}
+
+/// Transfers the 64-byte PIF RAM into `out`, and waits for the transfer to finish.
+/// 
+/// This gives raw access to PIF RAM, for protocols not covered by the higher level helpers.
+/// 
+/// # Safety
+/// `out` is written by DMA, so it must be located in RDRAM (not in ROM, or a memory mapped
+/// register), and must be 8-byte aligned. The data cache is not updated by DMA transfers, so `out`
+/// should either be accessed through an uncached (KSEG1) address, or any cached copy invalidated
+/// before reading it. No other SI transfer may be in progress.
+pub unsafe fn read_pif_ram(out: &mut [u8; 64]) -> Result<(), SiError> {
+    debug_assert!(out.as_ptr() as u32 & 0x7 == 0, "PIF RAM buffer must be 8-byte aligned");
+    
+    dma_from_pif(PIF_RAM_ADDR, out.as_mut_ptr() as u32)
+}
+
+/// Transfers `data` into the 64-byte PIF RAM, and waits for the transfer to finish.
+/// 
+/// # Safety
+/// `data` is read by DMA, so it must be located in RDRAM, and must be 8-byte aligned. The data
+/// cache is not read by DMA transfers, so any cached copy of `data` must be written back
+/// beforehand. No other SI transfer may be in progress.
+pub unsafe fn write_pif_ram(data: &[u8; 64]) -> Result<(), SiError> {
+    debug_assert!(data.as_ptr() as u32 & 0x7 == 0, "PIF RAM buffer must be 8-byte aligned");
+    
+    dma_to_pif(data.as_ptr() as u32, PIF_RAM_ADDR)
+}
+
+/// Writes a command block into PIF RAM, then reads back the processed block.
+unsafe fn pif_exchange(block: &[u8; 64]) -> Result<[u8; 64], SiError> {
+    let buffer = ((addr_of_mut!(PIF_BUFFER) as u32) | 0xA0000000) as *mut [u8; 64];
+    
+    buffer.write_volatile(*block);
+    write_pif_ram(&*buffer)?;
+    read_pif_ram(&mut *buffer)?;
+    
+    Ok(buffer.read_volatile())
+}
+
+/// Reads the state of all four controller ports using a single PIF transaction.
+/// 
+/// Ports without a connected controller are returned as `None`. If either SI transfer fails or
+/// times out, all ports are returned as `None`; use [`try_controller_poll_all()`] to find out why.
+/// 
+/// # Safety
+/// This function performs two SI DMA transfers, and acknowledges the resulting SI interrupts.
+/// It must not be called while another SI transfer is in progress.
+pub unsafe fn controller_poll_all() -> [Option<ControllerState>; 4] {
+    try_controller_poll_all().unwrap_or([None; 4])
+}
+
+/// Reads the state of all four controller ports using a single PIF transaction, reporting
+/// whether the transaction failed.
+/// 
+/// Returns [`SiError::Timeout`] if the SI got stuck, in which case [`reset()`] should be called
+/// before the next transaction.
+/// 
+/// # Safety
+/// See [`controller_poll_all()`].
+pub unsafe fn try_controller_poll_all() -> Result<[Option<ControllerState>; 4], SiError> {
+    pif_exchange(&joybus::controller_poll_block())
+        .map(|response| joybus::parse_controller_poll(&response))
+}
+
+/// Identifies the device connected to controller port `channel` (0 to 3), using the joybus info
+/// command.
+/// 
+/// Returns `None` if no device responded, or the SI transfer failed.
+/// 
+/// # Safety
+/// See [`controller_poll_all()`].
+pub unsafe fn identify_device(channel: usize) -> Option<DeviceType> {
+    let response = pif_exchange(&joybus::channel_command_block(channel, joybus::CMD_INFO, 3)).ok()?;
+    let info = joybus::parse_channel_response(&response, channel, 3)?;
+    
+    Some(DeviceType::from_type_word(u16::from_be_bytes([info[0], info[1]])))
+}
+
+/// Reads the motion and buttons of an N64 mouse connected to controller port `channel` (0 to 3).
+/// 
+/// The device type isn't checked; reading a standard controller returns its analog stick position
+/// as motion. Use [`identify_device()`] to check for a mouse first.
+/// 
+/// Returns `None` if no device responded, or the SI transfer failed.
+/// 
+/// # Safety
+/// See [`controller_poll_all()`].
+pub unsafe fn read_mouse(channel: usize) -> Option<MouseState> {
+    let response = pif_exchange(&joybus::channel_command_block(channel, joybus::CMD_CONTROLLER_STATE, 4)).ok()?;
+    let data = joybus::parse_channel_response(&response, channel, 4)?;
+    
+    Some(MouseState::from_bytes([data[0], data[1], data[2], data[3]]))
+}
+
+/// Errors reported by SI helper functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SiError {
+    /// The `dma_error` flag is set in the `SI_STATUS` register.
+    DmaError,
+    /// The transfer didn't finish within [`DMA_TIMEOUT`] cycles.
+    Timeout,
+}
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct StatusReg(pub u32): Debug {
         pub whole_register: u32 [wo] @ ..,
         
@@ -68,7 +397,9 @@ bitfield! {
         pub io_busy: bool [ro] @ 1,
         pub read_pending: bool [ro] @ 2,
         pub dma_error: bool [ro] @ 3,
+        /// Raw state of the SI's PIF channel state machine. See [`StatusReg::pch_state_decoded()`].
         pub pch_state: u8 [ro] @ 4..=7,
+        /// Raw state of the SI's DMA state machine. See [`StatusReg::dma_state_decoded()`].
         pub dma_state: u8 [ro] @ 8..=11,
         
         /// Mirror of the SI interrupt flag from the `MI_INTERRUPT` register.
@@ -76,7 +407,65 @@ bitfield! {
         /// Writing any value to the `SI_STATUS` register clears the flag across all three locations
         /// (this bit, `MI_INTERRUPT`, and the RCP Interrupt Cause register).
         /// 
-        /// SI Interrupts occur when a DMA write finishes.
+        /// SI Interrupts occur when a DMA write finishes. [`dma_to_pif()`] and [`dma_from_pif()`]
+        /// acknowledge the interrupt automatically.
         pub interrupt: bool @ 12,
     }
-}
\ No newline at end of file
+}
+impl_pod!(StatusReg);
+impl StatusReg {
+    /// Decodes [`StatusReg::pch_state()`].
+    #[must_use]
+    pub fn pch_state_decoded(&self) -> PchState {
+        PchState::from(self.pch_state())
+    }
+    
+    /// Decodes [`StatusReg::dma_state()`].
+    #[must_use]
+    pub fn dma_state_decoded(&self) -> DmaState {
+        DmaState::from(self.dma_state())
+    }
+}
+
+/// Decoded state of the SI's PIF channel state machine, as reported in `SI_STATUS`.
+/// 
+/// Only the idle state is documented. Other values are reported as [`PchState::Unknown`] with the
+/// raw value, so they can be compared against known good captures when diagnosing a stuck transfer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PchState {
+    /// No PIF access is in progress.
+    Idle,
+    /// An undocumented state, with its raw value.
+    Unknown(u8),
+}
+impl From<u8> for PchState {
+    fn from(value: u8) -> Self {
+        match value & 0xF {
+            0 => Self::Idle,
+            state => Self::Unknown(state),
+        }
+    }
+}
+
+/// Decoded state of the SI's DMA state machine, as reported in `SI_STATUS`.
+/// 
+/// Values without a known meaning are reported as [`DmaState::Unknown`] with the raw value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DmaState {
+    /// No DMA is in progress.
+    Idle,
+    /// The DMA has been issued, and is waiting for the PIF to acknowledge it (state 6). A transfer
+    /// stuck here means the PIF isn't responding.
+    WaitingForAck,
+    /// An undocumented state, with its raw value.
+    Unknown(u8),
+}
+impl From<u8> for DmaState {
+    fn from(value: u8) -> Self {
+        match value & 0xF {
+            0 => Self::Idle,
+            6 => Self::WaitingForAck,
+            state => Self::Unknown(state),
+        }
+    }
+}
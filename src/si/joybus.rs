@@ -0,0 +1,230 @@
+//! Joybus protocol used by the PIF to communicate with controllers and accessories.
+//! 
+//! Commands are sent by writing a command block into PIF RAM, and reading the block back once the
+//! PIF has processed it. Each command within the block is made of a transmit length byte, a receive
+//! length byte, the transmitted bytes (starting with the command ID), and space for the received
+//! bytes. The PIF overwrites the received bytes with the device's response, and sets error flags in
+//! the upper bits of the receive length byte.
+
+use proc_bitfield::bitfield;
+
+/// Reads the device type and status of a joybus device.
+pub const CMD_INFO: u8 = 0x00;
+/// Reads the button and analog stick state of a controller.
+pub const CMD_CONTROLLER_STATE: u8 = 0x01;
+
+/// Padding byte, skipped by the PIF while parsing a command block.
+pub const BLOCK_PAD: u8 = 0xFF;
+/// Marks the end of the commands in a command block.
+pub const BLOCK_END: u8 = 0xFE;
+/// Value of the final command block byte which tells the PIF to process the joybus commands.
+pub const BLOCK_START_JOYBUS: u8 = 0x01;
+
+/// Set in the receive length byte of a response if no device responded on the channel.
+pub const RX_NO_DEVICE: u8 = 0x80;
+/// Set in the receive length byte of a response if the device sent more data than expected.
+pub const RX_OVERRUN: u8 = 0x40;
+
+bitfield! {
+    /// Button state reported by a standard controller.
+    #[must_use]
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct Buttons(pub u16): Debug {
+        pub c_right: bool @ 0,
+        pub c_left: bool @ 1,
+        pub c_down: bool @ 2,
+        pub c_up: bool @ 3,
+        pub r: bool @ 4,
+        pub l: bool @ 5,
+        /// Set when L, R, and Start were pressed together to recalibrate the analog stick.
+        pub reset: bool @ 7,
+        pub d_right: bool @ 8,
+        pub d_left: bool @ 9,
+        pub d_down: bool @ 10,
+        pub d_up: bool @ 11,
+        pub start: bool @ 12,
+        pub z: bool @ 13,
+        pub b: bool @ 14,
+        pub a: bool @ 15,
+    }
+}
+impl_pod!(Buttons);
+
+/// State of a standard controller, as returned by the [`CMD_CONTROLLER_STATE`] command.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ControllerState {
+    pub buttons: Buttons,
+    /// Analog stick X position; negative is left, positive is right.
+    pub x: i8,
+    /// Analog stick Y position; negative is down, positive is up.
+    pub y: i8,
+}
+impl ControllerState {
+    /// Parses the 4-byte response of the [`CMD_CONTROLLER_STATE`] command.
+    #[inline]
+    pub fn from_bytes(data: [u8; 4]) -> Self {
+        Self {
+            buttons: Buttons(u16::from_be_bytes([data[0], data[1]])),
+            x: data[2] as i8,
+            y: data[3] as i8,
+        }
+    }
+}
+
+/// Builds a command block which reads the state of all four controller ports in one PIF transaction.
+/// 
+/// Each port's command occupies 8 bytes: a padding byte, the transmit and receive lengths, the
+/// command ID, and 4 bytes for the response.
+#[must_use]
+pub fn controller_poll_block() -> [u8; 64] {
+    let mut block = [0u8; 64];
+    for port in 0..4 {
+        block[(port * 8)..(port * 8 + 8)].copy_from_slice(&[
+            BLOCK_PAD, 0x01, 0x04, CMD_CONTROLLER_STATE,
+            0xFF, 0xFF, 0xFF, 0xFF,
+        ]);
+    }
+    block[32] = BLOCK_END;
+    block[63] = BLOCK_START_JOYBUS;
+    
+    block
+}
+
+/// Parses a block built by [`controller_poll_block()`] after it was processed by the PIF.
+/// 
+/// Ports without a responding controller are returned as `None`.
+pub fn parse_controller_poll(block: &[u8; 64]) -> [Option<ControllerState>; 4] {
+    let mut states = [None; 4];
+    for (port, state) in states.iter_mut().enumerate() {
+        let cmd = &block[(port * 8)..(port * 8 + 8)];
+        if cmd[2] & (RX_NO_DEVICE | RX_OVERRUN) == 0 {
+            *state = Some(ControllerState::from_bytes([cmd[4], cmd[5], cmd[6], cmd[7]]));
+        }
+    }
+    
+    states
+}
+
+/// Type of a joybus device, as reported in the first two bytes of the [`CMD_INFO`] response.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeviceType {
+    /// Standard controller (`0x0500`).
+    Controller,
+    /// N64 mouse (`0x0200`). See [`MouseState`].
+    Mouse,
+    /// Voice recognition unit (`0x0001`).
+    Vru,
+    /// Randnet keyboard (`0x0002`).
+    Keyboard,
+    /// Any other device, with its raw type word.
+    Unknown(u16),
+}
+impl DeviceType {
+    /// Decodes the (big endian) type word of a [`CMD_INFO`] response.
+    pub fn from_type_word(word: u16) -> Self {
+        match word {
+            0x0500 => Self::Controller,
+            0x0200 => Self::Mouse,
+            0x0001 => Self::Vru,
+            0x0002 => Self::Keyboard,
+            word => Self::Unknown(word),
+        }
+    }
+}
+
+/// State of an N64 mouse, as returned by the [`CMD_CONTROLLER_STATE`] command.
+/// 
+/// The mouse responds in the controller format, but the analog bytes hold the motion since the
+/// previous read, rather than an absolute position.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MouseState {
+    /// Left (`a`) and right (`b`) mouse buttons. Other buttons are never set.
+    pub buttons: Buttons,
+    /// Horizontal motion since the previous read; negative is left, positive is right.
+    pub dx: i8,
+    /// Vertical motion since the previous read; negative is down, positive is up.
+    pub dy: i8,
+}
+impl MouseState {
+    /// Parses the 4-byte response of the [`CMD_CONTROLLER_STATE`] command.
+    #[inline]
+    pub fn from_bytes(data: [u8; 4]) -> Self {
+        let state = ControllerState::from_bytes(data);
+        Self { buttons: state.buttons, dx: state.x, dy: state.y }
+    }
+}
+
+/// Builds a command block which sends the single-byte command `cmd` to `channel`, expecting
+/// `rx_len` bytes in response.
+/// 
+/// Earlier channels are skipped with a zero byte each. The response can be extracted with
+/// [`parse_channel_response()`].
+/// 
+/// # Panics
+/// Panics if `channel` is greater than 4, or `rx_len` is greater than 32.
+#[must_use]
+pub fn channel_command_block(channel: usize, cmd: u8, rx_len: u8) -> [u8; 64] {
+    assert!(channel <= 4, "joybus channel must be 0 to 4");
+    assert!(rx_len <= 32, "response can be at most 32 bytes");
+    
+    let mut block = [0u8; 64];
+    block[channel..(channel + 3)].copy_from_slice(&[0x01, rx_len, cmd]);
+    let end = channel + 3 + rx_len as usize;
+    block[(channel + 3)..end].fill(0xFF);
+    block[end] = BLOCK_END;
+    block[63] = BLOCK_START_JOYBUS;
+    
+    block
+}
+
+/// Extracts the response from a block built by [`channel_command_block()`] after it was processed
+/// by the PIF.
+/// 
+/// Returns `None` if no device responded, or the device sent more data than expected.
+#[must_use]
+pub fn parse_channel_response(block: &[u8; 64], channel: usize, rx_len: u8) -> Option<&[u8]> {
+    if block[channel + 1] & (RX_NO_DEVICE | RX_OVERRUN) != 0 {
+        return None;
+    }
+    
+    Some(&block[(channel + 3)..(channel + 3 + rx_len as usize)])
+}
+
+/// XOR term of [`address_crc()`] for each of address bits 5 through 15.
+const ADDRESS_CRC_TERMS: [u8; 11] = [0x15, 0x1F, 0x0B, 0x16, 0x19, 0x07, 0x0E, 0x1C, 0x0D, 0x1A, 0x01];
+
+/// Computes the 5-bit CRC which accessory read/write commands append to the 32-byte aligned
+/// accessory address.
+/// 
+/// The CRC uses the polynomial x^5 + x^4 + x^2 + 1 (`0x15`), over the 11 address bits 15 to 5. It's
+/// equivalent to XOR-ing together a fixed term for every set address bit. The low 5 bits of `addr`
+/// are ignored. The address sent to the accessory is `(addr & !0x1F) | address_crc(addr)`.
+#[must_use]
+pub fn address_crc(addr: u16) -> u8 {
+    ADDRESS_CRC_TERMS.iter()
+        .enumerate()
+        .filter(|(i, _)| addr & (1 << (i + 5)) != 0)
+        .fold(0, |crc, (_, term)| crc ^ term)
+}
+
+/// Computes the 8-bit CRC which an accessory returns after a 32-byte data read or write.
+/// 
+/// The CRC uses the polynomial x^8 + x^7 + x^2 + 1 (`0x85`), with no initial value or final XOR.
+/// The data is shifted in most significant bit first, followed by 8 zero bits. Comparing the
+/// result to the CRC returned by the accessory detects a corrupted or incomplete transfer.
+#[must_use]
+pub fn data_crc(data: &[u8; 32]) -> u8 {
+    let mut crc = 0u8;
+    for byte in data.iter().copied().chain(core::iter::once(0)) {
+        for bit in (0..8).rev() {
+            let feedback = crc & 0x80 != 0;
+            crc = (crc << 1) | ((byte >> bit) & 1);
+            if feedback {
+                crc ^= 0x85;
+            }
+        }
+    }
+    
+    crc
+}
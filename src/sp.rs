@@ -0,0 +1,345 @@
+//! RCP - Signal Processor (RSP)
+
+use core::ops::Deref;
+use proc_bitfield::bitfield;
+use crate::{RO, RW};
+
+/// Address of the 4KB data memory (DMEM), as seen by the CPU.
+pub const DMEM_ADDR: u32 = 0xA4000000;
+/// Address of the 4KB instruction memory (IMEM), as seen by the CPU.
+pub const IMEM_ADDR: u32 = 0xA4001000;
+/// Size in bytes of both DMEM and IMEM.
+pub const MEM_SIZE: usize = 0x1000;
+
+/// A wrapper around a mutable reference to the Signal Processor's memory mapped registers.
+/// 
+/// See [`SignalProcessor::new()`] for usage details.
+pub struct SignalProcessor {
+    r: &'static mut RegisterBlock,
+    
+    /// RSP program counter. Located separately from the other registers, at `0xA4080000`.
+    pub pc: &'static RW<u32>,
+}
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub mem_addr: RW<MemAddrReg>,
+    pub dram_addr: RW<u32>,
+    pub rd_len: RW<LenReg>,
+    pub wr_len: RW<LenReg>,
+    pub status: RW<StatusReg>,
+    pub dma_full: RO<u32>,
+    pub dma_busy: RO<u32>,
+    pub semaphore: RW<u32>,
+}
+impl SignalProcessor {
+    /// Creates a new wrapped mutable reference to the Signal Processor's memory mapped registers, starting at `0xA4040000`.
+    /// 
+    /// Developers are recommended to use [`Hardware::take()`][crate::Hardware::take()] instead.
+    /// But for unrestricted, unsafe, access, this struct provides a method-based version to the
+    /// static functions available at the [module][crate::sp] level.
+    /// 
+    /// # Safety
+    /// This provides unrestricted access to memory mapped registers. Data races _could_ occur if writing
+    /// to a register in both regular code and inside interrupt handlers.
+    /// 
+    /// This is especially problematic if performing a read-modify-write operation; an interrupt
+    /// could trigger between reading a register, and writing a modified value back to the same
+    /// register. Thus anything written to that register inside the interrupt, would only apply for
+    /// a short moment before being overwritten.
+    #[inline(always)]
+    pub unsafe fn new() -> Self { Self {
+        r: &mut *(0xA4040000 as *mut RegisterBlock),
+        pc: &*(0xA4080000 as *const RW<u32>),
+    }}
+}
+impl Deref for SignalProcessor {
+    type Target = RegisterBlock;
+    
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.r
+    }
+}
+
+regfn_rw!(SignalProcessor, mem_addr, MEM_ADDR, MemAddrReg);
+regfn_rw!(SignalProcessor, dram_addr, DRAM_ADDR, u32);
+regfn_rw!(SignalProcessor, rd_len, RD_LEN, LenReg);
+regfn_rw!(SignalProcessor, wr_len, WR_LEN, LenReg);
+regfn_rw_union!(SignalProcessor, status, STATUS, StatusReg);
+regfn_ro!(SignalProcessor, dma_full, DMA_FULL, u32);
+regfn_ro!(SignalProcessor, dma_busy, DMA_BUSY, u32);
+regfn_rw!(SignalProcessor, semaphore, SEMAPHORE, u32);
+regfn_rw!(SignalProcessor, pc, PC, u32);
+
+monitor_table!("SP", [
+    monitor_reg!(SignalProcessor, mem_addr, rw, MemAddrReg),
+    monitor_reg!(SignalProcessor, dram_addr, rw),
+    monitor_reg!(SignalProcessor, rd_len, rw, LenReg),
+    monitor_reg!(SignalProcessor, wr_len, rw, LenReg),
+    monitor_reg!(SignalProcessor, status, rw_union, StatusReg),
+    monitor_reg!(SignalProcessor, dma_full, ro),
+    monitor_reg!(SignalProcessor, dma_busy, ro),
+    // Reading acquires the semaphore
+    monitor_reg!(@ SignalProcessor, semaphore, [semaphore],
+        read: Some(semaphore),
+        write: Some(set_semaphore),
+        decode: None,
+        read_side_effects: true),
+    monitor_reg!(SignalProcessor, pc, rw),
+],
+    /// `SP_SEMAPHORE` is printed as skipped without being read, as reading it acquires the semaphore.
+);
+
+/// Errors reported by SP helper functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SpError {
+    /// A buffer is larger than the 4KB IMEM or DMEM it is being loaded into.
+    TooLarge,
+    /// A buffer is not aligned to 8 bytes.
+    Unaligned,
+}
+
+/// Blocks until the current SP DMA transfer has finished.
+#[inline(always)]
+pub fn wait_dma() {
+    let _ = unsafe { SignalProcessor::new() }.status.wait_until(|status| !status.decoded().dma_busy());
+}
+
+/// Blocks until the RSP has halted.
+#[inline(always)]
+pub fn wait_for_halt() {
+    while !status().halted() {}
+}
+
+/// Returns true if the RSP is halted.
+#[must_use]
+#[inline(always)]
+pub fn is_halted() -> bool {
+    status().halted()
+}
+
+/// Returns true if the RSP has executed a `break` instruction since the broke flag was last cleared.
+#[must_use]
+#[inline(always)]
+pub fn is_broken() -> bool {
+    status().broke()
+}
+
+/// Enables or disables single-step mode, in which the RSP halts after each instruction.
+#[inline(always)]
+pub unsafe fn set_single_step(enable: bool) {
+    set_status(if enable { StatusRegWrite(0).set_single_step() } else { StatusRegWrite(0).clear_single_step() });
+}
+
+/// Enables or disables raising the SP interrupt when the RSP executes a `break` instruction.
+#[inline(always)]
+pub unsafe fn set_interrupt_on_break(enable: bool) {
+    set_status(if enable { StatusRegWrite(0).set_interrupt_on_break() } else { StatusRegWrite(0).clear_interrupt_on_break() });
+}
+
+/// Returns the eight general purpose signals, with signal 0 in bit 0.
+/// 
+/// The signals have no hardware function, and are used for handshaking between the CPU and
+/// microcode, which can read and write them through its own view of `SP_STATUS`.
+#[must_use]
+#[inline(always)]
+pub fn read_signals() -> u8 {
+    status().signals()
+}
+
+/// Sets signal `n` (0-7), leaving the others unchanged.
+/// 
+/// # Panics
+/// Panics if `n` is out of range.
+#[inline(always)]
+pub unsafe fn set_signal(n: u8) {
+    set_status(StatusRegWrite(0).set_signal(n));
+}
+
+/// Clears signal `n` (0-7), leaving the others unchanged.
+/// 
+/// # Panics
+/// Panics if `n` is out of range.
+#[inline(always)]
+pub unsafe fn clear_signal(n: u8) {
+    set_status(StatusRegWrite(0).clear_signal(n));
+}
+
+/// Clears all eight signals.
+#[inline(always)]
+pub unsafe fn clear_signals() {
+    set_status(StatusRegWrite(0).clear_signals());
+}
+
+/// Transfers `len` bytes from RDRAM into IMEM or DMEM, and waits for the transfer to finish.
+/// 
+/// `dram` is the RDRAM address (8-byte aligned), and may be given as a physical, KSEG0, or KSEG1
+/// address. `mem_addr` is the offset within the selected memory. `len` is rounded up to a multiple
+/// of 8 bytes by the hardware, and must be between 1 and [`MEM_SIZE`].
+/// 
+/// The data cache is not read by DMA transfers, so any cached copy of the source must be written
+/// back beforehand.
+pub unsafe fn dma_to_mem(dram: u32, mem_addr: u16, imem: bool, len: u16) {
+    while status().dma_full() {}
+    set_mem_addr(MemAddrReg(0).with_addr(mem_addr).with_imem(imem));
+    set_dram_addr(dram & 0x1FFFFFFF);
+    set_rd_len(LenReg(0).with_length(len - 1));
+    wait_dma();
+}
+
+/// Loads and starts a RSP program.
+/// 
+/// The RSP is halted, `code` is transferred to the start of IMEM, `data` is transferred to the start
+/// of DMEM, the program counter is set to `entry_pc`, and finally the halt and broke flags are
+/// cleared together to start execution. Use [`wait_for_halt()`] to wait for the program to finish.
+/// 
+/// Both buffers must be 8-byte aligned, at most 4KB, and written back from the data cache.
+pub unsafe fn run_ucode(code: &[u8], data: &[u8], entry_pc: u32) -> Result<(), SpError> {
+    if code.len() > MEM_SIZE || data.len() > MEM_SIZE {
+        return Err(SpError::TooLarge);
+    }
+    if (code.as_ptr() as usize) % 8 != 0 || (data.as_ptr() as usize) % 8 != 0 {
+        return Err(SpError::Unaligned);
+    }
+    
+    set_status(StatusRegWrite(0).set_halt());
+    wait_dma();
+    
+    if !code.is_empty() {
+        dma_to_mem(code.as_ptr() as u32, 0, true, code.len() as u16);
+    }
+    if !data.is_empty() {
+        dma_to_mem(data.as_ptr() as u32, 0, false, data.len() as u16);
+    }
+    
+    set_pc(entry_pc & 0xFFC);
+    set_status(StatusRegWrite(0).clear_broke().clear_halt());
+    
+    Ok(())
+}
+
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct MemAddrReg(pub u32): Debug {
+        /// Offset within IMEM or DMEM (8-byte aligned)
+        pub addr: u16 @ 0..=11,
+        
+        /// Selects the memory used for the transfer
+        /// 
+        /// - 0 = DMEM
+        /// - 1 = IMEM
+        pub imem: bool @ 12,
+    }
+}
+impl_pod!(MemAddrReg);
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct LenReg(pub u32): Debug {
+        /// Number of bytes to transfer per row, minus 1 (rounded up to a multiple of 8)
+        pub length: u16 @ 0..=11,
+        
+        /// Number of rows to transfer, minus 1
+        pub count: u8 @ 12..=19,
+        
+        /// Number of bytes to skip in RDRAM after each row
+        pub skip: u16 @ 20..=31,
+    }
+}
+impl_pod!(LenReg);
+
+#[must_use]
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub union StatusReg {
+    pub raw: u32,
+    pub read: StatusRegRead,
+    pub write: StatusRegWrite,
+}
+impl_pod!(StatusReg);
+impl_union_reg!(StatusReg);
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct StatusRegRead(pub u32): Debug {
+        pub halted: bool [ro] @ 0,
+        pub broke: bool [ro] @ 1,
+        pub dma_busy: bool [ro] @ 2,
+        pub dma_full: bool [ro] @ 3,
+        pub io_full: bool [ro] @ 4,
+        pub single_step: bool [ro] @ 5,
+        pub interrupt_on_break: bool [ro] @ 6,
+        pub signals: u8 [ro] @ 7..=14,
+    }
+}
+impl_pod!(StatusRegRead);
+
+bitfield! {
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct StatusRegWrite(pub u32): Debug {
+        clear_halt: bool [wo] @ 0,
+        set_halt: bool [wo] @ 1,
+        clear_broke: bool [wo] @ 2,
+        clear_interrupt: bool [wo] @ 3,
+        set_interrupt: bool [wo] @ 4,
+        clear_single_step: bool [wo] @ 5,
+        set_single_step: bool [wo] @ 6,
+        clear_interrupt_on_break: bool [wo] @ 7,
+        set_interrupt_on_break: bool [wo] @ 8,
+    }
+}
+impl_pod!(StatusRegWrite);
+impl StatusRegWrite {
+    #[inline(always)]
+    pub fn clear_halt(self) -> Self { self.with_clear_halt(true) }
+    #[inline(always)]
+    pub fn set_halt(self) -> Self { self.with_set_halt(true) }
+    
+    #[inline(always)]
+    pub fn clear_broke(self) -> Self { self.with_clear_broke(true) }
+    
+    #[inline(always)]
+    pub fn clear_interrupt(self) -> Self { self.with_clear_interrupt(true) }
+    #[inline(always)]
+    pub fn set_interrupt(self) -> Self { self.with_set_interrupt(true) }
+    
+    #[inline(always)]
+    pub fn clear_single_step(self) -> Self { self.with_clear_single_step(true) }
+    #[inline(always)]
+    pub fn set_single_step(self) -> Self { self.with_set_single_step(true) }
+    
+    #[inline(always)]
+    pub fn clear_interrupt_on_break(self) -> Self { self.with_clear_interrupt_on_break(true) }
+    #[inline(always)]
+    pub fn set_interrupt_on_break(self) -> Self { self.with_set_interrupt_on_break(true) }
+    
+    /// Clears signal `n` (0-7). The signals' clear/set bit pairs start at bit 9.
+    /// 
+    /// Panics if `n` is out of range.
+    #[inline(always)]
+    pub fn clear_signal(self, n: u8) -> Self { self.with_signal_bit(n, 0) }
+    /// Sets signal `n` (0-7).
+    /// 
+    /// Panics if `n` is out of range.
+    #[inline(always)]
+    pub fn set_signal(self, n: u8) -> Self { self.with_signal_bit(n, 1) }
+    /// Clears all eight signals.
+    #[inline(always)]
+    pub fn clear_signals(self) -> Self { Self(self.0 | 0x00AAAA00) }
+    
+    #[inline(always)]
+    fn with_signal_bit(self, n: u8, set: u32) -> Self {
+        assert!(n < 8, "SP signal index out of range");
+        Self(self.0 | (1 << (9 + (n as u32 * 2) + set)))
+    }
+}
@@ -1,9 +1,12 @@
 //! RCP - Video Interface
 
 use core::ops::Deref;
+use core::ptr::{addr_of, addr_of_mut};
 use num_enum::{FromPrimitive, IntoPrimitive};
 use proc_bitfield::bitfield;
-use crate::RW;
+use crate::{cp0, poll_until, RW};
+
+pub mod text;
 
 /// A wrapper around a mutable reference to the Video Interface's memory mapped registers.
 /// 
@@ -18,6 +21,10 @@ pub struct RegisterBlock {
     pub origin: RW<u32>,
     pub width: RW<u32>,
     pub v_intr: RW<u32>,
+    /// Aliased register: reads return the current half-line, but any write acknowledges the VI
+    /// interrupt. A read-modify-write of this register therefore always acknowledges the interrupt.
+    /// 
+    /// Use [`current_halfline()`] and [`acknowledge_interrupt()`] instead.
     pub v_current: RW<u32>,
     pub burst: RW<BurstReg>,
     pub v_sync: RW<u32>,
@@ -28,7 +35,13 @@ pub struct RegisterBlock {
     pub v_burst: RW<VBurstReg>,
     pub x_scale: RW<XScaleReg>,
     pub y_scale: RW<YScaleReg>,
+    /// Diagnostic register selecting an address within the VI's internal test RAM (bits 0-6).
+    /// 
+    /// Not a data register; see [`read_staged()`] and [`write_staged()`].
     pub test_addr: RW<u32>,
+    /// Diagnostic register accessing the VI test RAM word selected by `test_addr`.
+    /// 
+    /// Not a data register; see [`read_staged()`] and [`write_staged()`].
     pub staged_data: RW<u32>,
 }
 impl VideoInterface {
@@ -64,7 +77,6 @@ regfn_rw!(VideoInterface, ctrl, CTRL, CtrlReg);
 regfn_rw!(VideoInterface, origin, ORIGIN, u32);
 regfn_rw!(VideoInterface, width, WIDTH, u32);
 regfn_rw!(VideoInterface, v_intr, V_INTR, u32);
-regfn_rw!(VideoInterface, v_current, V_CURRENT, u32);
 regfn_rw!(VideoInterface, burst, BURST, BurstReg);
 regfn_rw!(VideoInterface, v_sync, V_SYNC, u32);
 regfn_rw!(VideoInterface, h_sync, H_SYNC, HSyncReg);
@@ -77,6 +89,518 @@ regfn_rw!(VideoInterface, y_scale, Y_SCALE, YScaleReg);
 regfn_rw!(VideoInterface, test_addr, TEST_ADDR, u32);
 regfn_rw!(VideoInterface, staged_data, STAGED_DATA, u32);
 
+monitor_table!("VI", [
+    monitor_reg!(VideoInterface, ctrl, rw, CtrlReg),
+    monitor_reg!(VideoInterface, origin, rw),
+    monitor_reg!(VideoInterface, width, rw),
+    monitor_reg!(VideoInterface, v_intr, rw),
+    // Any write acknowledges the VI interrupt, so writes go through `acknowledge_interrupt()` to
+    // apply a framebuffer queued by `flip()`. The written value is ignored.
+    monitor_reg!(@ VideoInterface, v_current, [v_current],
+        read: Some(|| current_halfline() as u32),
+        write: Some(|_| unsafe { acknowledge_interrupt() }),
+        decode: None,
+        read_side_effects: false),
+    monitor_reg!(VideoInterface, burst, rw, BurstReg),
+    monitor_reg!(VideoInterface, v_sync, rw),
+    monitor_reg!(VideoInterface, h_sync, rw, HSyncReg),
+    monitor_reg!(VideoInterface, h_sync_leap, rw, HSyncLeapReg),
+    monitor_reg!(VideoInterface, h_video, rw, HVideoReg),
+    monitor_reg!(VideoInterface, v_video, rw, VVideoReg),
+    monitor_reg!(VideoInterface, v_burst, rw, VBurstReg),
+    monitor_reg!(VideoInterface, x_scale, rw, XScaleReg),
+    monitor_reg!(VideoInterface, y_scale, rw, YScaleReg),
+    monitor_reg!(VideoInterface, test_addr, rw),
+    monitor_reg!(VideoInterface, staged_data, rw),
+]);
+
+/// Reads a word from the VI's internal test RAM.
+/// 
+/// `VI_TEST_ADDR` and `VI_STAGED_DATA` form an indirect access pair: the address is first selected
+/// through `VI_TEST_ADDR`, then the data is accessed through `VI_STAGED_DATA`. Only the low 7 bits
+/// of `addr` are used.
+/// 
+/// These registers are only intended for hardware diagnostics, and their behavior outside of the
+/// VI's test mode ([`CtrlReg::test_mode()`]) is not well documented.
+/// 
+/// # Safety
+/// Overwrites `VI_TEST_ADDR`.
+#[must_use]
+#[inline(always)]
+pub unsafe fn read_staged(addr: u8) -> u32 {
+    set_test_addr((addr & 0x7F) as u32);
+    staged_data()
+}
+
+/// Writes a word to the VI's internal test RAM.
+/// 
+/// See [`read_staged()`] for how the address is selected.
+/// 
+/// # Safety
+/// Overwrites `VI_TEST_ADDR`. The effect of writing test RAM on the video output is undefined.
+#[inline(always)]
+pub unsafe fn write_staged(addr: u8, data: u32) {
+    set_test_addr((addr & 0x7F) as u32);
+    set_staged_data(data);
+}
+
+/// Enables or disables the VI's gamma correction, and the dithering applied alongside it.
+/// 
+/// The gamma curve is fixed in hardware (roughly a square root, to counter the NTSC/PAL encoders'
+/// gamma), and there's no gamma RAM which could be loaded with a custom ramp; the test RAM behind
+/// [`write_staged()`] is only for diagnostics. Custom tone mapping must be applied to the
+/// framebuffer contents instead, with hardware gamma disabled.
+/// 
+/// Dithering reduces banding caused by the correction, and has no effect unless `enable` is set.
+#[inline(always)]
+pub unsafe fn set_gamma(enable: bool, dither: bool) {
+    modify_ctrl(|ctrl| ctrl.with_gamma_enable(enable).with_gamma_dither_enable(dither));
+}
+
+/// Sentinel value of [`PENDING_ORIGIN`] indicating no origin is waiting to be applied.
+const NO_PENDING_ORIGIN: u32 = u32::MAX;
+
+/// Framebuffer origin queued by [`flip()`], applied by [`acknowledge_interrupt()`].
+static mut PENDING_ORIGIN: u32 = NO_PENDING_ORIGIN;
+
+/// Returns the half-line currently being scanned out, from the `VI_V_CURRENT` register.
+/// 
+/// This is in the same units as `VI_V_VIDEO` and `VI_V_INTR`. In interlaced modes, bit 0 holds the
+/// current field (see [`current_field()`]).
+/// 
+/// `VI_V_CURRENT` is only read here; writing it acknowledges the VI interrupt, which is done by
+/// [`acknowledge_interrupt()`]. No `set_`/`modify_` function is provided for it, as a
+/// read-modify-write would acknowledge the interrupt as a side effect.
+#[must_use]
+#[inline(always)]
+pub fn current_halfline() -> u16 {
+    (unsafe { VideoInterface::new() }.v_current.read() & 0x3FF) as u16
+}
+
+/// Returns `true` if the VI is currently outside the active display region (vertical blank).
+/// 
+/// The current half-line from `VI_V_CURRENT` is compared against the active region configured in
+/// `VI_V_VIDEO`.
+#[must_use]
+#[inline(always)]
+pub fn in_vblank() -> bool {
+    let line = current_halfline();
+    let video = v_video();
+    
+    line < video.v_start() || line >= video.v_end()
+}
+
+/// One of the two fields of an interlaced video frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Field {
+    Even,
+    Odd,
+}
+
+/// Returns the field currently being scanned out.
+/// 
+/// In interlaced modes (with [`CtrlReg::serrate()`] set), the VI reports the current field in bit 0
+/// of `VI_V_CURRENT`. In progressive modes every frame reports the same field.
+#[must_use]
+#[inline(always)]
+pub fn current_field() -> Field {
+    if current_halfline() & 1 == 0 {
+        Field::Even
+    } else {
+        Field::Odd
+    }
+}
+
+/// Snapshot of the VI's current raster position. See [`raster_position()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RasterPos {
+    /// Raw half-line counter from `VI_V_CURRENT` (bits 0 to 9), in the same units as `VI_V_VIDEO`
+    /// and `VI_V_INTR`.
+    pub half_line: u16,
+    /// Line currently being scanned out, counted from the start of the field (`half_line / 2`).
+    pub line: u16,
+    /// Field currently being scanned out. Always the same field in progressive modes.
+    pub field: Field,
+    /// Whether the line is outside the active display region configured in `VI_V_VIDEO`.
+    pub in_vblank: bool,
+}
+
+/// Returns the current raster position, decoded from a single read of `VI_V_CURRENT`.
+/// 
+/// Calling [`in_vblank()`] and [`current_field()`] separately reads `VI_V_CURRENT` each time, so
+/// the results may describe different lines. This reads it once, so every field of the returned
+/// [`RasterPos`] is consistent.
+#[must_use]
+#[inline]
+pub fn raster_position() -> RasterPos {
+    let half_line = current_halfline();
+    let video = v_video();
+    
+    RasterPos {
+        half_line,
+        line: half_line >> 1,
+        field: if half_line & 1 == 0 { Field::Even } else { Field::Odd },
+        in_vblank: half_line < video.v_start() || half_line >= video.v_end(),
+    }
+}
+
+/// Blocks until the VI begins scanning out `field`.
+/// 
+/// In interlaced modes, the even and odd fields show alternate lines of the image, so double
+/// buffered rendering must swap framebuffers in step with the fields, or the two fields will show
+/// mismatched content. Calling this before swapping ensures the swap happens at the start of the
+/// intended field.
+/// 
+/// If the VI is already in `field`, this waits for it to begin again (two fields later). In
+/// progressive modes, where [`CtrlReg::serrate()`] is cleared, there is only one field, so this
+/// instead waits for the start of the next frame.
+pub fn wait_for_field(field: Field) {
+    if !ctrl().serrate() {
+        let mut previous = current_halfline() & 0x3FE;
+        loop {
+            let line = current_halfline() & 0x3FE;
+            if line < previous {
+                return;
+            }
+            previous = line;
+        }
+    }
+    
+    while current_field() == field {}
+    while current_field() != field {}
+}
+
+/// Acknowledges a pending VI interrupt, by writing to the `VI_V_CURRENT` register.
+/// 
+/// This is the only function which writes `VI_V_CURRENT`. The written value is ignored by the VI.
+/// 
+/// If a framebuffer swap was queued by [`flip()`], the new origin is written before acknowledging.
+/// This function is intended to be called from the VI interrupt handler.
+#[inline(always)]
+pub unsafe fn acknowledge_interrupt() {
+    let pending = addr_of!(PENDING_ORIGIN).read_volatile();
+    if pending != NO_PENDING_ORIGIN {
+        addr_of_mut!(PENDING_ORIGIN).write_volatile(NO_PENDING_ORIGIN);
+        set_origin(pending);
+    }
+    
+    VideoInterface::new().v_current.write(0);
+}
+
+/// Swaps the displayed framebuffer to `next` without tearing.
+/// 
+/// The VI latches `VI_ORIGIN` once per field, so the write must happen while the beam is outside
+/// the active display region. If this function is called during vertical blank, the origin is
+/// written immediately and `true` is returned. Otherwise the origin is queued, `false` is returned,
+/// and the write is performed by the next call to [`acknowledge_interrupt()`]. Calling `flip()`
+/// again before then replaces the queued origin.
+/// 
+/// For the queued mode to work, `VI_V_INTR` must be set to a half-line within vertical blank (such
+/// as the last line of the active region), and the VI interrupt handler must call
+/// [`acknowledge_interrupt()`].
+/// 
+/// `next` must have the same dimensions and depth as the currently displayed framebuffer.
+#[inline(always)]
+pub unsafe fn flip(next: &Framebuffer) -> bool {
+    let origin = next.origin();
+    
+    if in_vblank() {
+        addr_of_mut!(PENDING_ORIGIN).write_volatile(NO_PENDING_ORIGIN);
+        set_origin(origin);
+        true
+    } else {
+        addr_of_mut!(PENDING_ORIGIN).write_volatile(origin);
+        false
+    }
+}
+
+/// Sets the framebuffer line stride, by writing the `VI_WIDTH` register.
+/// 
+/// Despite its name, `VI_WIDTH` is the distance in pixels between the start of each line in RDRAM,
+/// not the width of the displayed image. The displayed width is determined by the active region
+/// (`VI_H_VIDEO`) and horizontal scale (`VI_X_SCALE`). The stride must be equal to or greater than
+/// the displayed width; a stride which doesn't match the buffer's actual layout causes each line to
+/// be shifted from the previous one, skewing the image diagonally.
+/// 
+/// Using a stride wider than the displayed width allows scrolling horizontally within a larger
+/// buffer, by moving `VI_ORIGIN`. `pixels` is limited to 12 bits.
+#[inline(always)]
+pub unsafe fn set_stride(pixels: u16) {
+    set_width((pixels & 0xFFF) as u32);
+}
+
+/// Immediately points the VI at the provided framebuffer, by writing the `VI_ORIGIN` and `VI_WIDTH`
+/// registers.
+/// 
+/// This may cause tearing if called outside of vertical blank. See [`flip()`] for a tear-free swap.
+#[inline(always)]
+pub unsafe fn set_framebuffer(fb: &Framebuffer) {
+    set_origin(fb.origin());
+    set_stride(fb.stride());
+}
+
+/// Configures the VI to display the provided framebuffer using the timings of the provided video mode.
+/// 
+/// The VI is blanked while the timing registers are written, and the control register is written
+/// last to start output. The framebuffer is scaled to fill the mode's active display region, and
+/// the VI interrupt is set to trigger on half-line 2, within vertical blank.
+pub unsafe fn setup(fb: &Framebuffer, mode: VideoMode) {
+    setup_region(fb, mode, mode.h_video(), mode.v_video());
+}
+
+/// Like [`setup()`], but displays the framebuffer with square pixels, at the correct aspect ratio on
+/// a standard 4:3 TV.
+/// 
+/// The mode's active region is displayed as 4:3, so a 320x240 or 640x480 framebuffer fills it
+/// exactly. Other sizes are shrunk on one axis (see [`aspect_correct_region()`]), and centered
+/// with black borders. Overscan crops roughly the same proportion of both axes of the active
+/// region, so it doesn't affect the aspect ratio; important content should still stay within
+/// the central ~90%.
+pub unsafe fn setup_aspect_correct(fb: &Framebuffer, mode: VideoMode) {
+    let (h_video, v_video) = aspect_correct_region(mode.h_video(), mode.v_video(), fb.visible_width(), fb.height());
+    setup_region(fb, mode, h_video, v_video);
+}
+
+/// Returns the part of the active region (`h_video` in VI clocks, `v_video` in half-lines) which
+/// displays a `width` by `height` framebuffer with square pixels.
+/// 
+/// The full active region is assumed to be displayed as 4:3. The region keeps the full height if
+/// the framebuffer is 4:3 or narrower, and the full width otherwise. It's centered within the
+/// active region, keeping the vertical start on the same half-line parity.
+#[must_use]
+pub fn aspect_correct_region(h_video: HVideoReg, v_video: VVideoReg, width: u16, height: u16) -> (HVideoReg, VVideoReg) {
+    let active_width = (h_video.h_end() - h_video.h_start()) as u32;
+    let active_height = (v_video.v_end() - v_video.v_start()) as u32;
+    let (width, height) = (width.max(1) as u32, height.max(1) as u32);
+    
+    // Width which keeps the pixels square while using the full height
+    let full_height_width = (width * 3 * active_width) / (4 * height);
+    let (region_width, region_height) = if full_height_width <= active_width {
+        (full_height_width, active_height)
+    } else {
+        (active_width, (height * 4 * active_height) / (3 * width))
+    };
+    
+    let h_start = h_video.h_start() + ((active_width - region_width) / 2) as u16;
+    let v_start = v_video.v_start() + (((active_height - region_height) / 2) & !1) as u16;
+    (
+        HVideoReg(0).with_h_start(h_start).with_h_end(h_start + region_width as u16),
+        VVideoReg(0).with_v_start(v_start).with_v_end(v_start + region_height as u16),
+    )
+}
+
+/// Number of half-lines [`probe_connected_standard()`] measures over.
+const PROBE_HALF_LINES: u16 = 400;
+
+/// Number of CP0 Count cycles (roughly two NTSC fields) [`probe_connected_standard()`] waits for
+/// `VI_V_CURRENT` to reach each point of the measurement.
+const PROBE_TIMEOUT: u32 = 1_600_000;
+
+/// Detects the video standard the console outputs, by measuring the VI clock against the CP0 Count
+/// register.
+/// 
+/// The field rate only reflects the timings programmed into the VI, so it can't tell which standard
+/// the console (and the TV it's connected to) actually uses. Instead, this times 400 half-lines of
+/// `VI_V_CURRENT` with Count, and computes the VI clock from the line length in `VI_H_SYNC`. The VI
+/// clock is generated by a region-specific crystal (see [`VideoMode::vi_clock_hz()`]), so it
+/// identifies the console's video hardware even on modded or region-free consoles, where the CIC
+/// region doesn't.
+/// 
+/// The VI must already be running (for example configured with [`setup()`]) with any progressive or
+/// interlaced timings. The measurement takes one to two fields, with interrupts disabled so they
+/// can't delay it. Returns `None` if `VI_V_CURRENT` isn't advancing, or the measured clock doesn't
+/// match any known standard. Count is assumed to run at
+/// [`COUNT_HZ`][crate::cp0::timer::COUNT_HZ], as on retail consoles.
+#[must_use]
+pub fn probe_connected_standard() -> Option<VideoMode> {
+    let line_clocks = h_sync().h_sync() as u64 + 1;
+    
+    cp0::interrupt_free(|| {
+        // Start near the top of the field, so the measured span never wraps
+        let start = wait_for_halfline(|line| line < 0x20)?;
+        let (start_line, start_count) = wait_for_halfline(|line| line != start.0)?;
+        let (end_line, end_count) = wait_for_halfline(|line| line >= start_line + PROBE_HALF_LINES)?;
+        
+        let half_lines = (end_line - start_line) as u64;
+        let cycles = end_count.wrapping_sub(start_count) as u64;
+        let hz = half_lines * line_clocks * cp0::timer::COUNT_HZ as u64 / (2 * cycles.max(1));
+        
+        VideoMode::from_vi_clock_hz(hz as u32)
+    })
+}
+
+/// Polls `VI_V_CURRENT` (without the field bit) until `pred` returns true for it, returning the
+/// half-line and the Count value when it did, or `None` after [`PROBE_TIMEOUT`] cycles.
+fn wait_for_halfline(pred: impl Fn(u16) -> bool) -> Option<(u16, u32)> {
+    poll_until(|| (current_halfline() & 0x3FE, cp0::count()), |(line, _)| pred(line), Some(PROBE_TIMEOUT)).ok()
+}
+
+/// A rectangle in framebuffer pixel coordinates.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+impl Rect {
+    /// Returns true if the pixel at `(x, y)` is inside the rectangle.
+    #[must_use]
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// The safe areas of a framebuffer, as returned by [`safe_area()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SafeArea {
+    /// Central 90% of each axis. Anything outside may be cropped by the TV's overscan, so gameplay
+    /// elements should stay inside.
+    pub action: Rect,
+    /// Central 80% of each axis. Text and HUD elements should stay inside, as some TVs crop even
+    /// more, and the edges of the picture are often distorted.
+    pub title: Rect,
+}
+
+/// Returns the action-safe and title-safe areas of a `width` by `height` framebuffer displayed by
+/// [`setup()`] or [`setup_aspect_correct()`] at full height.
+/// 
+/// These are the standard broadcast safe areas: 5% of each axis is cropped from each side for the
+/// action-safe area, and 10% for the title-safe area. Every [`VideoMode`]'s preset active region
+/// covers the same proportion of the picture, so the areas are the same for every mode. If the
+/// active region has been shrunk to the action-safe area with [`set_active_region_safe()`], the
+/// whole framebuffer is visible, and the margins aren't needed.
+#[must_use]
+pub fn safe_area(width: u16, height: u16) -> SafeArea {
+    let inset = |percent: u32| {
+        let x = (width as u32 * percent / 100) as u16;
+        let y = (height as u32 * percent / 100) as u16;
+        Rect { x, y, width: width - 2 * x, height: height - 2 * y }
+    };
+    
+    SafeArea { action: inset(5), title: inset(10) }
+}
+
+/// Returns the central 90% (the action-safe area) of the active region described by `h_video` (in
+/// VI clocks) and `v_video` (in half-lines), keeping the vertical start on the same half-line parity.
+#[must_use]
+pub fn action_safe_region(h_video: HVideoReg, v_video: VVideoReg) -> (HVideoReg, VVideoReg) {
+    let active_width = h_video.h_end() - h_video.h_start();
+    let active_height = v_video.v_end() - v_video.v_start();
+    let h_margin = (active_width as u32 * 5 / 100) as u16;
+    let v_margin = ((active_height as u32 * 5 / 100) as u16) & !1;
+    
+    (
+        HVideoReg(0).with_h_start(h_video.h_start() + h_margin).with_h_end(h_video.h_end() - h_margin),
+        VVideoReg(0).with_v_start(v_video.v_start() + v_margin).with_v_end(v_video.v_end() - v_margin),
+    )
+}
+
+/// Like [`setup()`], but shrinks the active region to the mode's action-safe area (see
+/// [`action_safe_region()`]), so the whole framebuffer stays visible despite overscan.
+/// 
+/// The framebuffer is scaled down to fit, with black borders around it, so UI can be placed right
+/// up to the framebuffer's edges. The aspect ratio is unchanged.
+pub unsafe fn set_active_region_safe(fb: &Framebuffer, mode: VideoMode) {
+    let (h_video, v_video) = action_safe_region(mode.h_video(), mode.v_video());
+    setup_region(fb, mode, h_video, v_video);
+}
+
+/// Implements [`setup()`], displaying the framebuffer within the provided active region.
+unsafe fn setup_region(fb: &Framebuffer, mode: VideoMode, h_video: HVideoReg, v_video: VVideoReg) {
+    let active_width = (h_video.h_end() - h_video.h_start()) as u32;
+    let active_lines = ((v_video.v_end() - v_video.v_start()) / 2) as u32;
+    
+    let config = Config {
+        ctrl: CtrlReg(0)
+            .with_depth(fb.depth())
+            .with_aa_mode(AntiAliasMode::ResamplingOnly)
+            .with_pixel_advance(3),
+        origin: fb.origin(),
+        width: (fb.stride() & 0xFFF) as u32,
+        v_intr: 2,
+        burst: mode.burst(),
+        v_sync: mode.v_sync(),
+        h_sync: mode.h_sync(),
+        h_sync_leap: mode.h_sync_leap(),
+        h_video,
+        v_video,
+        v_burst: mode.v_burst(),
+        x_scale: XScaleReg(0).with_x_scale((((fb.visible_width() as u32) << 10) / active_width) as u16),
+        y_scale: YScaleReg(0).with_y_scale((((fb.height() as u32) << 10) / active_lines) as u16),
+    };
+    // Framebuffer::new() already rejects unaligned origins and invalid depths
+    configure(&config).expect("framebuffer produced an invalid VI config");
+}
+
+/// Complete VI configuration, written by [`configure()`].
+/// 
+/// [`setup()`] builds one of these from a [`Framebuffer`] and [`VideoMode`]; this allows every
+/// register to be chosen explicitly, while keeping the same write order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    /// Written last, once every other register has been configured.
+    pub ctrl: CtrlReg,
+    /// Physical RDRAM address of the framebuffer, 8-byte aligned.
+    pub origin: u32,
+    /// Framebuffer stride, in pixels.
+    pub width: u32,
+    /// Half-line which raises the VI interrupt.
+    pub v_intr: u32,
+    /// Sync and color burst pulse widths (`VI_BURST`).
+    pub burst: BurstReg,
+    /// Number of half-lines per field, minus one (`VI_V_SYNC`).
+    pub v_sync: u32,
+    /// Line length in VI clocks minus one, and the leap pattern (`VI_H_SYNC`).
+    pub h_sync: HSyncReg,
+    /// Alternate line lengths used by the leap pattern (`VI_H_SYNC_LEAP`).
+    pub h_sync_leap: HSyncLeapReg,
+    /// Horizontal start and end of the active display region (`VI_H_VIDEO`).
+    pub h_video: HVideoReg,
+    /// Vertical start and end of the active display region, in half-lines (`VI_V_VIDEO`).
+    pub v_video: VVideoReg,
+    /// Vertical start and end of the color burst, in half-lines (`VI_V_BURST`).
+    pub v_burst: VBurstReg,
+    /// Horizontal framebuffer scale and subpixel offset (`VI_X_SCALE`).
+    pub x_scale: XScaleReg,
+    /// Vertical framebuffer scale and subpixel offset (`VI_Y_SCALE`).
+    pub y_scale: YScaleReg,
+}
+
+/// Validates `config`, then writes it in the order the VI requires.
+/// 
+/// The VI is blanked first, so it never scans out with a mix of old and new timings. The timing
+/// registers are written next, then the framebuffer, interrupt line, and scaling, and `VI_CTRL` is
+/// written last to start output. Nothing is written if the config is invalid: `ctrl` must pass
+/// [`CtrlReg::validate()`], and `origin` must be 8-byte aligned.
+pub unsafe fn configure(config: &Config) -> Result<(), ViConfigError> {
+    config.ctrl.validate()?;
+    if config.origin & 0x7 != 0 {
+        return Err(ViConfigError::UnalignedOrigin);
+    }
+    
+    set_ctrl(CtrlReg(0).with_depth(ColorDepth::Blank));
+    
+    set_burst(config.burst);
+    set_v_sync(config.v_sync);
+    set_h_sync(config.h_sync);
+    set_h_sync_leap(config.h_sync_leap);
+    set_h_video(config.h_video);
+    set_v_video(config.v_video);
+    set_v_burst(config.v_burst);
+    
+    set_origin(config.origin);
+    set_width(config.width);
+    set_v_intr(config.v_intr);
+    set_x_scale(config.x_scale);
+    set_y_scale(config.y_scale);
+    
+    set_ctrl(config.ctrl);
+    
+    Ok(())
+}
+
+
 
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -88,6 +612,22 @@ pub enum AntiAliasMode {
     Enabled = 0,
 }
 
+/// Packs 8-bit color channels into an RGBA5551 pixel, as used by 16-bit framebuffers.
+/// 
+/// The lowest 3 bits of each color channel are discarded.
+#[must_use]
+#[inline(always)]
+pub const fn rgba16(r: u8, g: u8, b: u8, a: bool) -> u16 {
+    ((r as u16 >> 3) << 11) | ((g as u16 >> 3) << 6) | ((b as u16 >> 3) << 1) | (a as u16)
+}
+
+/// Packs 8-bit color channels into an RGBA8888 pixel, as used by 32-bit framebuffers.
+#[must_use]
+#[inline(always)]
+pub const fn rgba32(r: u8, g: u8, b: u8, a: u8) -> u32 {
+    ((r as u32) << 24) | ((g as u32) << 16) | ((b as u32) << 8) | (a as u32)
+}
+
 #[derive(IntoPrimitive, FromPrimitive, Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
 pub enum ColorDepth {
@@ -99,7 +639,9 @@ pub enum ColorDepth {
 }
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct CtrlReg(pub u32): Debug {
         pub depth: u8 [ColorDepth] @ 0..=1,
         pub gamma_dither_enable: bool @ 2,
@@ -109,16 +651,64 @@ bitfield! {
         /// **Never** enable this bit! Early research indicates this could potentially damage the console if set to `true`.
         pub vbus_clock_enable: bool @ 5,
         pub serrate: bool @ 6,
+        /// Diagnostic use only. Enables the test path through the `VI_TEST_ADDR` and `VI_STAGED_DATA`
+        /// registers (see [`read_staged()`] and [`write_staged()`]).
+        /// 
+        /// While set, the VI's output is redirected to the diagnostic registers, and the displayed
+        /// image should be considered undefined. Leave this `false` for normal operation.
         pub test_mode: bool @ 7,
         pub aa_mode: u8 [AntiAliasMode] @ 8..=9,
+        /// Diagnostic use only. Disables the write enable of the VI's internal line buffers, so no new
+        /// data is fetched from the framebuffer.
+        /// 
+        /// While set, the VI stops updating its output, and keeps displaying stale line buffer
+        /// contents. Leave this `false` for normal operation.
         pub kill_we: bool @ 11,
         pub pixel_advance: u8 @ 12..=15,
         pub dither_filter_enable: bool @ 16,
     }
 }
+impl_pod!(CtrlReg);
+impl CtrlReg {
+    /// Checks the combination of color depth and filter settings for known conflicts.
+    /// 
+    /// Invalid combinations don't fail in any obvious way on hardware, they just produce a subtly
+    /// wrong image. No checks are performed when the depth is [`ColorDepth::Blank`].
+    /// 
+    /// The `serrate` bit isn't checked, as whether it should be set depends on the timing registers
+    /// (it must be set for interlaced modes, and cleared for progressive modes).
+    pub fn validate(&self) -> Result<(), ViConfigError> {
+        if self.vbus_clock_enable() {
+            return Err(ViConfigError::VbusClockEnabled);
+        }
+        
+        let depth = self.depth();
+        let aa_mode = self.aa_mode();
+        let anti_aliased = matches!(aa_mode, AntiAliasMode::Enabled | AntiAliasMode::EnabledAsNeeded);
+        let is_32bpp = depth == ColorDepth::BPP32;
+        
+        if depth == ColorDepth::Blank {
+            Ok(())
+        } else if depth == ColorDepth::Reserved {
+            Err(ViConfigError::ReservedDepth)
+        } else if is_32bpp && aa_mode == AntiAliasMode::Disabled {
+            Err(ViConfigError::ResamplingDisabledAt32Bpp)
+        } else if is_32bpp && self.dither_filter_enable() {
+            Err(ViConfigError::DitherFilterAt32Bpp)
+        } else if self.dither_filter_enable() && !anti_aliased {
+            Err(ViConfigError::DitherFilterWithoutAntiAlias)
+        } else if self.divot_enable() && !anti_aliased {
+            Err(ViConfigError::DivotWithoutAntiAlias)
+        } else {
+            Ok(())
+        }
+    }
+}
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct BurstReg(pub u32): Debug {
         pub hsync_width: u8 @ 0..=7,
         pub burst_width: u8 @ 8..=15,
@@ -126,59 +716,353 @@ bitfield! {
         pub burst_start: u16 @ 20..=29,
     }
 }
+impl_pod!(BurstReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct HSyncReg(pub u32): Debug {
         pub h_sync: u16 @ 0..=11,
         pub leap: u8 @ 16..=20,
     }
 }
+impl_pod!(HSyncReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct HSyncLeapReg(pub u32): Debug {
         pub leap_b: u16 @ 0..=9,
         pub leap_a: u16 @ 16..=25,
     }
 }
+impl_pod!(HSyncLeapReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct HVideoReg(pub u32): Debug {
         pub h_end: u16 @ 0..=9,
         pub h_start: u16 @ 16..=25,
     }
 }
+impl_pod!(HVideoReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct VVideoReg(pub u32): Debug {
         pub v_end: u16 @ 0..=9,
         pub v_start: u16 @ 16..=25,
     }
 }
+impl_pod!(VVideoReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct VBurstReg(pub u32): Debug {
         pub v_burst_end: u16 @ 0..=9,
         pub v_burst_start: u16 @ 16..=25,
     }
 }
+impl_pod!(VBurstReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct XScaleReg(pub u32): Debug {
         pub x_scale: u16 @ 0..=11,
         pub x_offset: u16 @ 16..=27,
     }
 }
+impl_pod!(XScaleReg);
 
 bitfield! {
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[must_use]
+    #[derive(Copy, Clone, Default, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct YScaleReg(pub u32): Debug {
         pub y_scale: u16 @ 0..=11,
         pub y_offset: u16 @ 16..=27,
     }
-}
\ No newline at end of file
+}
+impl_pod!(YScaleReg);
+
+/// Errors reported by VI helper functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ViError {
+    /// The framebuffer pointer is null, or is not aligned to 8 bytes.
+    UnalignedOrigin,
+    /// The framebuffer width or height is zero, the stride is narrower than the width, or the
+    /// stride doesn't fit in the `VI_WIDTH` register.
+    InvalidSize,
+    /// The framebuffer uses [`ColorDepth::Blank`] or [`ColorDepth::Reserved`].
+    InvalidDepth,
+}
+
+/// Conflicting `VI_CTRL` settings, reported by [`CtrlReg::validate()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ViConfigError {
+    /// The `vbus_clock_enable` bit is set, which could damage the console.
+    VbusClockEnabled,
+    /// The depth is [`ColorDepth::Reserved`].
+    ReservedDepth,
+    /// [`AntiAliasMode::Disabled`] (pixel replication) is only supported at 16bpp.
+    ResamplingDisabledAt32Bpp,
+    /// The dither filter only applies to 16bpp framebuffers, and must be disabled at 32bpp.
+    DitherFilterAt32Bpp,
+    /// The dither filter uses coverage data, so requires [`AntiAliasMode::Enabled`] or
+    /// [`AntiAliasMode::EnabledAsNeeded`].
+    DitherFilterWithoutAntiAlias,
+    /// The divot filter uses coverage data, so requires [`AntiAliasMode::Enabled`] or
+    /// [`AntiAliasMode::EnabledAsNeeded`].
+    DivotWithoutAntiAlias,
+    /// The framebuffer origin in a [`Config`] isn't 8-byte aligned.
+    UnalignedOrigin,
+}
+
+/// A validated framebuffer located in RDRAM.
+/// 
+/// Constructing a `Framebuffer` checks the alignment, size, and color depth up front, so that any
+/// function accepting one can rely on it being displayable by the VI.
+/// 
+/// A framebuffer has both a visible width, which is the number of pixels displayed per line, and a
+/// stride, which is the number of pixels between the start of each line in memory. These are equal
+/// unless the buffer was created with [`Framebuffer::with_stride()`]. See [`set_stride()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Framebuffer {
+    ptr: *mut u8,
+    width: u16,
+    stride: u16,
+    height: u16,
+    depth: ColorDepth,
+}
+impl Framebuffer {
+    /// Creates a new framebuffer handle, validating its alignment, size, and color depth.
+    /// 
+    /// # Safety
+    /// `ptr` must point to at least `width * height * bytes_per_pixel` bytes of RDRAM, which stay
+    /// valid for as long as this handle (or any copy of it) is used.
+    pub unsafe fn new(ptr: *mut u8, width: u16, height: u16, depth: ColorDepth) -> Result<Self, ViError> {
+        if ptr.is_null() || (ptr as usize) % 8 != 0 {
+            return Err(ViError::UnalignedOrigin);
+        }
+        if width == 0 || height == 0 || width > 0xFFF {
+            return Err(ViError::InvalidSize);
+        }
+        if depth != ColorDepth::BPP16 && depth != ColorDepth::BPP32 {
+            return Err(ViError::InvalidDepth);
+        }
+        
+        Ok(Self { ptr, width, stride: width, height, depth })
+    }
+    
+    /// Creates a new framebuffer handle whose lines are `stride` pixels apart in memory, of which
+    /// the first `width` pixels are displayed.
+    /// 
+    /// # Safety
+    /// `ptr` must point to at least `stride * height * bytes_per_pixel` bytes of RDRAM, which stay
+    /// valid for as long as this handle (or any copy of it) is used.
+    pub unsafe fn with_stride(ptr: *mut u8, width: u16, stride: u16, height: u16, depth: ColorDepth) -> Result<Self, ViError> {
+        if stride < width || stride > 0xFFF {
+            return Err(ViError::InvalidSize);
+        }
+        
+        let fb = Self::new(ptr, width, height, depth)?;
+        Ok(Self { stride, ..fb })
+    }
+    
+    /// Pointer to the first pixel of the framebuffer.
+    #[inline(always)]
+    pub fn ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+    
+    /// Visible width of the framebuffer in pixels. Same as [`visible_width()`][Self::visible_width()].
+    #[inline(always)]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+    
+    /// Number of pixels displayed per line.
+    #[inline(always)]
+    pub fn visible_width(&self) -> u16 {
+        self.width
+    }
+    
+    /// Number of pixels between the start of each line in memory, as expected by the `VI_WIDTH`
+    /// register.
+    #[inline(always)]
+    pub fn stride(&self) -> u16 {
+        self.stride
+    }
+    
+    /// Height of the framebuffer in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+    
+    /// Color depth of the framebuffer.
+    #[inline(always)]
+    pub fn depth(&self) -> ColorDepth {
+        self.depth
+    }
+    
+    /// Number of bytes used by each pixel.
+    #[inline(always)]
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self.depth {
+            ColorDepth::BPP32 => 4,
+            _ => 2,
+        }
+    }
+    
+    /// Total size of the framebuffer in bytes, including any pixels beyond the visible width.
+    #[inline(always)]
+    pub fn size_bytes(&self) -> usize {
+        self.stride as usize * self.height as usize * self.bytes_per_pixel()
+    }
+    
+    /// Physical address of the framebuffer, as expected by the `VI_ORIGIN` register.
+    #[inline(always)]
+    pub fn origin(&self) -> u32 {
+        (self.ptr as u32) & 0x1FFFFFFF
+    }
+    
+    /// Returns a mutable reference to the pixel at `(x, y)` of a 16-bit framebuffer.
+    /// 
+    /// `x` may be beyond the visible width, up to the stride. Returns `None` if the coordinates are out
+    /// of bounds, or if the framebuffer isn't 16-bit.
+    #[inline]
+    pub fn pixel16_mut(&mut self, x: u16, y: u16) -> Option<&mut u16> {
+        if self.depth != ColorDepth::BPP16 || x >= self.stride || y >= self.height {
+            return None;
+        }
+        
+        let offset = y as usize * self.stride as usize + x as usize;
+        Some(unsafe { &mut *(self.ptr as *mut u16).add(offset) })
+    }
+    
+    /// Returns a mutable reference to the pixel at `(x, y)` of a 32-bit framebuffer.
+    /// 
+    /// `x` may be beyond the visible width, up to the stride. Returns `None` if the coordinates are out
+    /// of bounds, or if the framebuffer isn't 32-bit.
+    #[inline]
+    pub fn pixel32_mut(&mut self, x: u16, y: u16) -> Option<&mut u32> {
+        if self.depth != ColorDepth::BPP32 || x >= self.stride || y >= self.height {
+            return None;
+        }
+        
+        let offset = y as usize * self.stride as usize + x as usize;
+        Some(unsafe { &mut *(self.ptr as *mut u32).add(offset) })
+    }
+}
+
+/// Standard video timings supported by [`setup()`].
+/// 
+/// The register presets describe a progressive (non-interlaced) display.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VideoMode {
+    /// 525 lines, 60Hz
+    Ntsc,
+    /// 625 lines, 50Hz
+    Pal,
+    /// 525 lines, 60Hz (Brazil)
+    Mpal,
+}
+impl VideoMode {
+    /// Every mode, in declaration order.
+    pub const ALL: [VideoMode; 3] = [Self::Ntsc, Self::Pal, Self::Mpal];
+    
+    /// Returns the frequency of the VI clock of consoles built for this mode.
+    /// 
+    /// Each region's console derives the VI clock from a different crystal, so the clock identifies
+    /// the video standard the console outputs, regardless of the timings programmed into the VI.
+    #[must_use]
+    pub fn vi_clock_hz(self) -> u32 {
+        match self {
+            Self::Ntsc => 48_681_812,
+            Self::Pal => 49_656_530,
+            Self::Mpal => 48_628_322,
+        }
+    }
+    
+    /// Returns the mode whose VI clock is within 0.05% of `hz`, or `None` if there isn't one.
+    /// 
+    /// The NTSC and MPAL clocks differ by roughly 0.11%, so at most one mode can match.
+    #[must_use]
+    pub fn from_vi_clock_hz(hz: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.vi_clock_hz().abs_diff(hz) < mode.vi_clock_hz() / 2000)
+    }
+    
+    /// Preset value for the `VI_BURST` register.
+    pub fn burst(self) -> BurstReg {
+        BurstReg(match self {
+            Self::Ntsc => 0x03E52239,
+            Self::Pal => 0x0404233A,
+            Self::Mpal => 0x04651E39,
+        })
+    }
+    
+    /// Preset value for the `VI_V_SYNC` register (number of half-lines per field).
+    pub fn v_sync(self) -> u32 {
+        match self {
+            Self::Ntsc => 0x20D,
+            Self::Pal => 0x271,
+            Self::Mpal => 0x20D,
+        }
+    }
+    
+    /// Preset value for the `VI_H_SYNC` register.
+    pub fn h_sync(self) -> HSyncReg {
+        HSyncReg(match self {
+            Self::Ntsc => 0x00000C15,
+            Self::Pal => 0x00150C69,
+            Self::Mpal => 0x00040C11,
+        })
+    }
+    
+    /// Preset value for the `VI_H_SYNC_LEAP` register.
+    pub fn h_sync_leap(self) -> HSyncLeapReg {
+        HSyncLeapReg(match self {
+            Self::Ntsc => 0x0C150C15,
+            Self::Pal => 0x0C6F0C6E,
+            Self::Mpal => 0x0C190C1A,
+        })
+    }
+    
+    /// Preset value for the `VI_H_VIDEO` register (active horizontal region).
+    pub fn h_video(self) -> HVideoReg {
+        HVideoReg(match self {
+            Self::Ntsc => 0x006C02EC,
+            Self::Pal => 0x00800300,
+            Self::Mpal => 0x006C02EC,
+        })
+    }
+    
+    /// Preset value for the `VI_V_VIDEO` register (active vertical region, in half-lines).
+    pub fn v_video(self) -> VVideoReg {
+        VVideoReg(match self {
+            Self::Ntsc => 0x002501FF,
+            Self::Pal => 0x005F0239,
+            Self::Mpal => 0x002501FF,
+        })
+    }
+    
+    /// Preset value for the `VI_V_BURST` register.
+    pub fn v_burst(self) -> VBurstReg {
+        VBurstReg(match self {
+            Self::Ntsc => 0x000E0204,
+            Self::Pal => 0x0009026B,
+            Self::Mpal => 0x000E0204,
+        })
+    }
+}
@@ -0,0 +1,192 @@
+//! Minimal text rendering, for on-screen debugging.
+//! 
+//! Glyphs are drawn from an embedded 8x8 monospace font covering printable ASCII (`0x20..=0x7E`).
+//! Each glyph occupies the top-left 5x8 pixels of its cell, with 7 pixels above the baseline and 1
+//! below. Text is drawn directly into a [`Framebuffer`], without any allocation.
+//! 
+//! Colors are in the framebuffer's native format: RGBA5551 for 16-bit framebuffers (see
+//! [`rgba16()`][super::rgba16()]), or RGBA8888 for 32-bit framebuffers (see [`rgba32()`][super::rgba32()]).
+//! Only foreground pixels are drawn, so text is drawn over the existing contents.
+//! 
+//! The font is the public domain "5x8" font from the X11 misc-fixed collection.
+
+use crate::vi::{ColorDepth, Framebuffer};
+
+/// Width of each character cell, in pixels. Characters are advanced by this amount.
+pub const GLYPH_WIDTH: u16 = 8;
+/// Height of each character cell, in pixels. Lines are advanced by this amount.
+pub const GLYPH_HEIGHT: u16 = 8;
+
+/// Glyphs for `' '..='~'`; the most significant bit of each byte is the leftmost pixel.
+static FONT: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x20, 0x20, 0x20, 0x20, 0x00, 0x20, 0x00], // !
+    [0x00, 0x50, 0x50, 0x50, 0x00, 0x00, 0x00, 0x00], // "
+    [0x50, 0x50, 0xF8, 0x50, 0xF8, 0x50, 0x50, 0x00], // #
+    [0x20, 0x70, 0xA0, 0x70, 0x28, 0x70, 0x20, 0x00], // $
+    [0x00, 0x40, 0x50, 0x20, 0x50, 0x10, 0x00, 0x00], // %
+    [0x40, 0xA0, 0xA0, 0x40, 0xA0, 0xA0, 0x50, 0x00], // &
+    [0x00, 0x20, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00], // '
+    [0x00, 0x20, 0x40, 0x40, 0x40, 0x40, 0x20, 0x00], // (
+    [0x00, 0x40, 0x20, 0x20, 0x20, 0x20, 0x40, 0x00], // )
+    [0x00, 0x00, 0x90, 0x60, 0xF0, 0x60, 0x90, 0x00], // *
+    [0x00, 0x00, 0x20, 0x20, 0xF8, 0x20, 0x20, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x20, 0x40], // ,
+    [0x00, 0x00, 0x00, 0x00, 0xF0, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x70, 0x20], // .
+    [0x00, 0x10, 0x10, 0x20, 0x40, 0x80, 0x80, 0x00], // /
+    [0x00, 0x20, 0x50, 0x50, 0x50, 0x50, 0x20, 0x00], // 0
+    [0x00, 0x20, 0x60, 0x20, 0x20, 0x20, 0x70, 0x00], // 1
+    [0x00, 0x60, 0x90, 0x10, 0x60, 0x80, 0xF0, 0x00], // 2
+    [0x00, 0xF0, 0x20, 0x60, 0x10, 0x90, 0x60, 0x00], // 3
+    [0x00, 0x20, 0x60, 0xA0, 0xF0, 0x20, 0x20, 0x00], // 4
+    [0x00, 0xF0, 0x80, 0xE0, 0x10, 0x90, 0x60, 0x00], // 5
+    [0x00, 0x60, 0x80, 0xE0, 0x90, 0x90, 0x60, 0x00], // 6
+    [0x00, 0xF0, 0x10, 0x20, 0x20, 0x40, 0x40, 0x00], // 7
+    [0x00, 0x60, 0x90, 0x60, 0x90, 0x90, 0x60, 0x00], // 8
+    [0x00, 0x60, 0x90, 0x90, 0x70, 0x10, 0x60, 0x00], // 9
+    [0x00, 0x00, 0x60, 0x60, 0x00, 0x60, 0x60, 0x00], // :
+    [0x00, 0x00, 0x30, 0x30, 0x00, 0x30, 0x20, 0x40], // ;
+    [0x00, 0x10, 0x20, 0x40, 0x40, 0x20, 0x10, 0x00], // <
+    [0x00, 0x00, 0x00, 0xF0, 0x00, 0xF0, 0x00, 0x00], // =
+    [0x00, 0x40, 0x20, 0x10, 0x10, 0x20, 0x40, 0x00], // >
+    [0x00, 0x20, 0x50, 0x10, 0x20, 0x00, 0x20, 0x00], // ?
+    [0x30, 0x48, 0x98, 0xA8, 0xA8, 0x90, 0x40, 0x30], // @
+    [0x00, 0x60, 0x90, 0x90, 0xF0, 0x90, 0x90, 0x00], // A
+    [0x00, 0xE0, 0x90, 0xE0, 0x90, 0x90, 0xE0, 0x00], // B
+    [0x00, 0x60, 0x90, 0x80, 0x80, 0x90, 0x60, 0x00], // C
+    [0x00, 0xE0, 0x90, 0x90, 0x90, 0x90, 0xE0, 0x00], // D
+    [0x00, 0xF0, 0x80, 0xE0, 0x80, 0x80, 0xF0, 0x00], // E
+    [0x00, 0xF0, 0x80, 0xE0, 0x80, 0x80, 0x80, 0x00], // F
+    [0x00, 0x60, 0x90, 0x80, 0xB0, 0x90, 0x60, 0x00], // G
+    [0x00, 0x90, 0x90, 0xF0, 0x90, 0x90, 0x90, 0x00], // H
+    [0x00, 0x70, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00], // I
+    [0x00, 0x70, 0x20, 0x20, 0x20, 0xA0, 0x40, 0x00], // J
+    [0x00, 0x90, 0xA0, 0xC0, 0xA0, 0xA0, 0x90, 0x00], // K
+    [0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0x00], // L
+    [0x00, 0x90, 0xF0, 0xF0, 0x90, 0x90, 0x90, 0x00], // M
+    [0x00, 0x90, 0xD0, 0xF0, 0xB0, 0xB0, 0x90, 0x00], // N
+    [0x00, 0x60, 0x90, 0x90, 0x90, 0x90, 0x60, 0x00], // O
+    [0x00, 0xE0, 0x90, 0x90, 0xE0, 0x80, 0x80, 0x00], // P
+    [0x00, 0x60, 0x90, 0x90, 0xD0, 0xB0, 0x60, 0x10], // Q
+    [0x00, 0xE0, 0x90, 0x90, 0xE0, 0x90, 0x90, 0x00], // R
+    [0x00, 0x60, 0x90, 0x40, 0x20, 0x90, 0x60, 0x00], // S
+    [0x00, 0x70, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00], // T
+    [0x00, 0x90, 0x90, 0x90, 0x90, 0x90, 0x60, 0x00], // U
+    [0x00, 0x90, 0x90, 0x90, 0x90, 0x60, 0x60, 0x00], // V
+    [0x00, 0x90, 0x90, 0x90, 0xF0, 0xF0, 0x90, 0x00], // W
+    [0x00, 0x90, 0x90, 0x60, 0x60, 0x90, 0x90, 0x00], // X
+    [0x00, 0x88, 0x88, 0x50, 0x20, 0x20, 0x20, 0x00], // Y
+    [0x00, 0xF0, 0x10, 0x20, 0x40, 0x80, 0xF0, 0x00], // Z
+    [0x00, 0x70, 0x40, 0x40, 0x40, 0x40, 0x70, 0x00], // [
+    [0x00, 0x80, 0x80, 0x40, 0x20, 0x10, 0x10, 0x00], // backslash
+    [0x00, 0x70, 0x10, 0x10, 0x10, 0x10, 0x70, 0x00], // ]
+    [0x00, 0x20, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF0], // _
+    [0x00, 0x40, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x00, 0x70, 0x90, 0x90, 0x70, 0x00], // a
+    [0x00, 0x80, 0x80, 0xE0, 0x90, 0x90, 0xE0, 0x00], // b
+    [0x00, 0x00, 0x00, 0x30, 0x40, 0x40, 0x30, 0x00], // c
+    [0x00, 0x10, 0x10, 0x70, 0x90, 0x90, 0x70, 0x00], // d
+    [0x00, 0x00, 0x00, 0x60, 0xB0, 0xC0, 0x60, 0x00], // e
+    [0x00, 0x20, 0x50, 0x40, 0xE0, 0x40, 0x40, 0x00], // f
+    [0x00, 0x00, 0x00, 0x60, 0x90, 0x70, 0x10, 0x60], // g
+    [0x00, 0x80, 0x80, 0xE0, 0x90, 0x90, 0x90, 0x00], // h
+    [0x00, 0x20, 0x00, 0x60, 0x20, 0x20, 0x70, 0x00], // i
+    [0x00, 0x10, 0x00, 0x10, 0x10, 0x10, 0x50, 0x20], // j
+    [0x00, 0x80, 0x80, 0x90, 0xE0, 0x90, 0x90, 0x00], // k
+    [0x00, 0x60, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00], // l
+    [0x00, 0x00, 0x00, 0xD0, 0xA8, 0xA8, 0xA8, 0x00], // m
+    [0x00, 0x00, 0x00, 0xE0, 0x90, 0x90, 0x90, 0x00], // n
+    [0x00, 0x00, 0x00, 0x60, 0x90, 0x90, 0x60, 0x00], // o
+    [0x00, 0x00, 0x00, 0xE0, 0x90, 0xE0, 0x80, 0x80], // p
+    [0x00, 0x00, 0x00, 0x70, 0x90, 0x70, 0x10, 0x10], // q
+    [0x00, 0x00, 0x00, 0xA0, 0xD0, 0x80, 0x80, 0x00], // r
+    [0x00, 0x00, 0x00, 0x30, 0x60, 0x10, 0x60, 0x00], // s
+    [0x00, 0x40, 0x40, 0xE0, 0x40, 0x50, 0x20, 0x00], // t
+    [0x00, 0x00, 0x00, 0x90, 0x90, 0x90, 0x70, 0x00], // u
+    [0x00, 0x00, 0x00, 0x50, 0x50, 0x50, 0x20, 0x00], // v
+    [0x00, 0x00, 0x00, 0x88, 0xA8, 0xA8, 0x50, 0x00], // w
+    [0x00, 0x00, 0x00, 0x90, 0x60, 0x60, 0x90, 0x00], // x
+    [0x00, 0x00, 0x00, 0x90, 0x90, 0x70, 0x90, 0x60], // y
+    [0x00, 0x00, 0x00, 0xF0, 0x20, 0x40, 0xF0, 0x00], // z
+    [0x30, 0x40, 0x20, 0xC0, 0x20, 0x40, 0x30, 0x00], // {
+    [0x00, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00], // |
+    [0xC0, 0x20, 0x40, 0x30, 0x40, 0x20, 0xC0, 0x00], // }
+    [0x00, 0x50, 0xA0, 0x00, 0x00, 0x00, 0x00, 0x00], // ~
+];
+
+/// Returns the glyph for `c`, or the glyph for `'?'` if `c` isn't printable ASCII.
+fn glyph(c: char) -> &'static [u8; 8] {
+    match c {
+        ' '..='~' => &FONT[c as usize - 0x20],
+        _ => &FONT['?' as usize - 0x20],
+    }
+}
+
+/// Writes a single pixel, ignoring pixels outside the framebuffer.
+#[inline(always)]
+fn put_pixel(fb: &Framebuffer, x: u16, y: u16, color: u32) {
+    if x >= fb.stride || y >= fb.height {
+        return;
+    }
+    
+    let offset = y as usize * fb.stride as usize + x as usize;
+    unsafe {
+        match fb.depth {
+            ColorDepth::BPP32 => (fb.ptr as *mut u32).add(offset).write_volatile(color),
+            _ => (fb.ptr as *mut u16).add(offset).write_volatile(color as u16),
+        }
+    }
+}
+
+/// Draws a single character with the top-left corner of its cell at `(x, y)`.
+/// 
+/// Characters outside of printable ASCII are drawn as `'?'`. Pixels outside of the framebuffer are
+/// clipped.
+pub fn draw_char(fb: &Framebuffer, x: u16, y: u16, color: u32, c: char) {
+    for (row, bits) in glyph(c).iter().enumerate() {
+        for col in 0..8 {
+            if bits & (0x80 >> col) != 0 {
+                put_pixel(fb, x.saturating_add(col), y.saturating_add(row as u16), color);
+            }
+        }
+    }
+}
+
+/// Draws a string with the top-left corner of its first cell at `(x, y)`.
+/// 
+/// A `'\n'` moves to the start of the next line, [`GLYPH_HEIGHT`] pixels down. Text isn't wrapped;
+/// anything beyond the edge of the framebuffer is clipped.
+pub fn draw_text(fb: &Framebuffer, x: u16, y: u16, color: u32, s: &str) {
+    let (mut cx, mut cy) = (x, y);
+    for c in s.chars() {
+        if c == '\n' {
+            cx = x;
+            cy = cy.saturating_add(GLYPH_HEIGHT);
+            continue;
+        }
+        
+        draw_char(fb, cx, cy, color, c);
+        cx = cx.saturating_add(GLYPH_WIDTH);
+    }
+}
+
+/// Draws the lowest `digits` hexadecimal digits of `value`, most significant first, in uppercase.
+fn draw_hex(fb: &Framebuffer, x: u16, y: u16, color: u32, value: u64, digits: u16) {
+    for i in 0..digits {
+        let digit = ((value >> ((digits - 1 - i) * 4)) & 0xF) as u32;
+        let c = char::from_digit(digit, 16).unwrap_or('?').to_ascii_uppercase();
+        draw_char(fb, x.saturating_add(i * GLYPH_WIDTH), y, color, c);
+    }
+}
+
+/// Draws `value` as 8 hexadecimal digits, without a prefix.
+pub fn draw_hex_u32(fb: &Framebuffer, x: u16, y: u16, color: u32, value: u32) {
+    draw_hex(fb, x, y, color, value as u64, 8);
+}
+
+/// Draws `value` as 16 hexadecimal digits, without a prefix.
+pub fn draw_hex_u64(fb: &Framebuffer, x: u16, y: u16, color: u32, value: u64) {
+    draw_hex(fb, x, y, color, value, 16);
+}
@@ -0,0 +1,914 @@
+//! Golden round-trip tests for every register bitfield.
+//! 
+//! Each test builds a register value from zero using the `with_*` builders, checks the raw value
+//! against a known constant, then checks every getter against the expected field values. This
+//! guards the hand-written bit ranges against accidental changes.
+//! 
+//! These tests don't touch hardware, and are intended to be run on the host machine:
+//! `cargo test --target <host-triple>`
+
+/// Asserts that each write builder sets exactly one bit, that no two builders share a bit, and that
+/// together they set exactly `mask`. Used to check the clear/set halves of union registers.
+fn assert_write_bits(bits: &[u32], mask: u32) {
+    let mut seen = 0;
+    for &bit in bits {
+        assert_eq!(bit.count_ones(), 1, "builder sets {:#X}, not a single bit", bit);
+        assert_eq!(seen & bit, 0, "bit {:#X} is set by more than one builder", bit);
+        seen |= bit;
+    }
+    assert_eq!(seen, mask);
+}
+
+mod cp0 {
+    use n64_pac::cp0::{BadVAddrReg, CacheAlgorithm, CauseReg, ConfigReg, ContextReg, EntryHiReg, EntryLoReg, ErrorExceptionPcReg, ExceptionCode, ExceptionPcReg, FreqRatio, IndexReg, PageMaskReg, PageSize, ParityErrorReg, ProcessorRevisionIdReg, RandomReg, StatusReg, TagLoReg, VAddrRegion, WatchHiReg, WatchLoReg, WiredReg, WritebackPattern, XContextReg, MASTER_CLOCK_HZ};
+    
+    #[test]
+    fn index_reg() {
+        let value = IndexReg(0)
+            .with_index(0x25)
+            .with_probe(true);
+        assert_eq!(value.0, 0x80000025);
+        
+        let value = IndexReg(0x80000025);
+        assert_eq!(value.index(), 0x25);
+        assert!(value.probe());
+    }
+    
+    #[test]
+    fn random_reg() {
+        let value = RandomReg(0x25);
+        assert_eq!(value.random(), 0x25);
+    }
+    
+    #[test]
+    fn entry_lo_reg() {
+        let value = EntryLoReg(0)
+            .with_global(true)
+            .with_valid(true)
+            .with_dirty(true)
+            .with_cache_algorithm(CacheAlgorithm::Cached)
+            .with_page_frame_number(0x955555);
+        assert_eq!(value.0, 0x2555555F);
+        
+        let value = EntryLoReg(0x2555555F);
+        assert!(value.global());
+        assert!(value.valid());
+        assert!(value.dirty());
+        assert_eq!(value.cache_algorithm(), CacheAlgorithm::Cached);
+        assert_eq!(value.page_frame_number(), 0x955555);
+    }
+    
+    #[test]
+    fn entry_lo_reg_for_page() {
+        let value = EntryLoReg::for_page(0x0012_3000, CacheAlgorithm::Uncached, true, true, false);
+        assert_eq!(value.page_frame_number(), 0x123);
+        assert_eq!(value.physical_address(), 0x0012_3000);
+        assert_eq!(value.cache_algorithm(), CacheAlgorithm::Uncached);
+        assert!(value.dirty());
+        assert!(value.valid());
+        assert!(!value.global());
+        assert_eq!(value.0, 0x48D6);
+    }
+    
+    #[test]
+    fn context_reg() {
+        let value = ContextReg(0)
+            .with_bad_vpn2(0x75555)
+            .with_pte_base_u32(0x155);
+        assert_eq!(value.0, 0xAAF55550);
+        
+        let value = ContextReg(0xAAF55550);
+        assert_eq!(value.bad_vpn2(), 0x75555);
+        assert_eq!(value.pte_base_u32(), 0x155);
+        assert_eq!(value.pte_base_u64(), 0x155);
+    }
+    
+    #[test]
+    fn page_mask_reg() {
+        let value = PageMaskReg(0)
+            .with_mask(PageSize::KB256);
+        assert_eq!(value.0, 0x7E000);
+        
+        let value = PageMaskReg(0x7E000);
+        assert_eq!(value.mask(), PageSize::KB256);
+    }
+    
+    #[test]
+    fn wired_reg() {
+        let value = WiredReg(0)
+            .with_wired(0x25);
+        assert_eq!(value.0, 0x25);
+        
+        let value = WiredReg(0x25);
+        assert_eq!(value.wired(), 0x25);
+    }
+    
+    #[test]
+    fn bad_v_addr_reg() {
+        let value = BadVAddrReg(0x95555555);
+        assert_eq!(value.badvaddr_u32(), 0x95555555);
+        assert_eq!(value.badvaddr_u64(), 0x95555555);
+    }
+    
+    #[test]
+    fn entry_hi_reg() {
+        let value = EntryHiReg(0)
+            .with_asid(0x95)
+            .with_vpn2_u32(0x55555)
+            .with_fill(0x355555)
+            .with_region(VAddrRegion::Supervisor);
+        assert_eq!(value.0, 0x75555500AAAAA095);
+        
+        let value = EntryHiReg(0x75555500AAAAA095);
+        assert_eq!(value.asid(), 0x95);
+        assert_eq!(value.vpn2_u32(), 0x55555);
+        assert_eq!(value.vpn2_u64(), 0x55555);
+        assert_eq!(value.fill(), 0x355555);
+        assert_eq!(value.region(), VAddrRegion::Supervisor);
+    }
+    
+    #[test]
+    fn status_reg() {
+        let value = StatusReg(0)
+            .with_ie(true)
+            .with_exl(true)
+            .with_erl(true)
+            .with_ksu(0x3)
+            .with_ux(true)
+            .with_sx(true)
+            .with_kx(true)
+            .with_im(0xD5)
+            .with_ds(0x1D5)
+            .with_re(true)
+            .with_fr(true)
+            .with_rp(true)
+            .with_cu(0xD);
+        assert_eq!(value.0, 0xDFD5D5FF);
+        
+        let value = StatusReg(0xDFD5D5FF);
+        assert!(value.ie());
+        assert!(value.exl());
+        assert!(value.erl());
+        assert_eq!(value.ksu(), 0x3);
+        assert!(value.ux());
+        assert!(value.sx());
+        assert!(value.kx());
+        assert_eq!(value.im(), 0xD5);
+        assert!(value.im_ip0());
+        assert!(!value.im_ip1());
+        assert!(value.im_int0());
+        assert!(!value.im_int1());
+        assert!(value.im_int2());
+        assert!(!value.im_int3());
+        assert!(value.im_int4());
+        assert!(value.im_timer());
+        assert_eq!(value.ds(), 0x1D5);
+        assert!(value.ds_de());
+        assert!(!value.ds_ce());
+        assert!(value.ds_ch());
+        assert!(value.ds_sr());
+        assert!(!value.ds_ts());
+        assert!(value.ds_bev());
+        assert!(value.ds_its());
+        assert!(value.re());
+        assert!(value.fr());
+        assert!(value.rp());
+        assert_eq!(value.cu(), 0xD);
+    }
+    
+    #[test]
+    fn cause_reg() {
+        let value = CauseReg(0)
+            .with_ip0(true)
+            .with_ip1(true);
+        assert_eq!(value.0, 0x300);
+        
+        let value = CauseReg(0xB000FF24);
+        assert_eq!(value.exception_code(), ExceptionCode::Breakpoint);
+        assert!(value.ip0());
+        assert!(value.ip1());
+        assert!(value.ip2());
+        assert!(value.ip3());
+        assert!(value.ip4());
+        assert!(value.ip5());
+        assert!(value.ip6());
+        assert!(value.ip7());
+        assert_eq!(value.ce(), 0x3);
+        assert!(value.branch_delay());
+    }
+    
+    #[test]
+    fn exception_pc_reg() {
+        let value = ExceptionPcReg(0)
+            .with_epc_u32(0x95555555);
+        assert_eq!(value.0, 0x95555555);
+        
+        let value = ExceptionPcReg(0x95555555);
+        assert_eq!(value.epc_u32(), 0x95555555);
+        assert_eq!(value.epc_u64(), 0x95555555);
+    }
+    
+    #[test]
+    fn processor_revision_id_reg() {
+        let value = ProcessorRevisionIdReg(0xD595);
+        assert_eq!(value.revision(), 0x95);
+        assert_eq!(value.processor_id(), 0xD5);
+    }
+    
+    #[test]
+    fn config_reg() {
+        let value = ConfigReg(0)
+            .with_k0(CacheAlgorithm::Cached)
+            .with_cu(true)
+            .with_be(true)
+            .with_ep(0xD);
+        assert_eq!(value.0, 0xD00800B);
+        
+        let value = ConfigReg(0x7D00800B);
+        assert_eq!(value.k0(), CacheAlgorithm::Cached);
+        assert!(value.cu());
+        assert!(value.be());
+        assert_eq!(value.ep(), 0xD);
+        assert_eq!(value.ec(), 0x7);
+    }
+    
+    #[test]
+    fn config_writeback_pattern() {
+        let value = ConfigReg(0x7D00800B);
+        assert_eq!(value.writeback_pattern(), None);
+        assert_eq!(value.with_writeback_pattern(WritebackPattern::DxxDxx).0, 0x7600800B);
+        assert_eq!(value.with_writeback_pattern(WritebackPattern::D).writeback_pattern(), Some(WritebackPattern::D));
+        assert_eq!(ConfigReg(0x06000000).writeback_pattern(), Some(WritebackPattern::DxxDxx));
+    }
+    
+    #[test]
+    fn status_presets() {
+        assert_eq!(StatusReg::integer_only().0, 0x00001400);
+        assert_eq!(StatusReg::fpu_game().0, 0x24001400);
+        assert!(!StatusReg::fpu_game().ie());
+    }
+    
+    #[test]
+    fn page_size_bytes() {
+        assert_eq!(PageSize::KB4.bytes(), Some(0x1000));
+        assert_eq!(PageSize::KB16.bytes(), Some(0x4000));
+        assert_eq!(PageSize::KB64.bytes(), Some(0x10000));
+        assert_eq!(PageSize::KB256.bytes(), Some(0x40000));
+        assert_eq!(PageSize::MB1.bytes(), Some(0x100000));
+        assert_eq!(PageSize::MB4.bytes(), Some(0x400000));
+        assert_eq!(PageSize::MB16.bytes(), Some(0x1000000));
+        assert_eq!(PageSize::Undefined.bytes(), None);
+    }
+    
+    #[test]
+    fn page_size_cache_display() {
+        assert_eq!(PageSize::KB4.to_string(), "4 KiB");
+        assert_eq!(PageSize::KB256.to_string(), "256 KiB");
+        assert_eq!(PageSize::MB1.to_string(), "1 MiB");
+        assert_eq!(PageSize::MB16.to_string(), "16 MiB");
+        assert_eq!(PageSize::Undefined.to_string(), "Undefined");
+        assert_eq!(CacheAlgorithm::Uncached.to_string(), "Uncached");
+        assert_eq!(CacheAlgorithm::Cached.to_string(), "Cached (writeback)");
+    }
+    
+    #[test]
+    fn config_reg_freq_ratio() {
+        assert_eq!(ConfigReg(0x60000000).freq_ratio(), FreqRatio::OneToOne);
+        assert_eq!(ConfigReg(0x70000000).freq_ratio(), FreqRatio::ThreeToTwo);
+        assert_eq!(ConfigReg(0x7D00800B).freq_ratio(), FreqRatio::ThreeToTwo);
+        assert_eq!(ConfigReg(0x00000000).freq_ratio(), FreqRatio::TwoToOne);
+        assert_eq!(ConfigReg(0x10000000).freq_ratio(), FreqRatio::ThreeToOne);
+        assert_eq!(ConfigReg(0x20000000).freq_ratio(), FreqRatio::Reserved(2));
+        
+        assert_eq!(FreqRatio::ThreeToTwo.pclock_hz(MASTER_CLOCK_HZ), Some(93_750_000));
+        assert_eq!(FreqRatio::Reserved(2).pclock_hz(MASTER_CLOCK_HZ), None);
+    }
+    
+    #[test]
+    fn watch_lo_reg() {
+        let value = WatchLoReg(0)
+            .with_w(true)
+            .with_r(true)
+            .with_paddr0(0x1D555555);
+        assert_eq!(value.0, 0xEAAAAAAB);
+        
+        let value = WatchLoReg(0xEAAAAAAB);
+        assert!(value.w());
+        assert!(value.r());
+        assert_eq!(value.paddr0(), 0x1D555555);
+    }
+    
+    #[test]
+    fn watch_hi_reg() {
+        let value = WatchHiReg(0)
+            .with_paddr1(0x9);
+        assert_eq!(value.0, 0x9);
+        
+        let value = WatchHiReg(0x9);
+        assert_eq!(value.paddr1(), 0x9);
+    }
+    
+    #[test]
+    fn x_context_reg() {
+        let value = XContextReg(0)
+            .with_badvpn2(0x7555555)
+            .with_region(VAddrRegion::Supervisor)
+            .with_ptebase(0x75555555);
+        assert_eq!(value.0, 0xEAAAAAAAF5555550);
+        
+        let value = XContextReg(0xEAAAAAAAF5555550);
+        assert_eq!(value.badvpn2(), 0x7555555);
+        assert_eq!(value.region(), VAddrRegion::Supervisor);
+        assert_eq!(value.ptebase(), 0x75555555);
+    }
+    
+    #[test]
+    fn parity_error_reg() {
+        let value = ParityErrorReg(0)
+            .with_diagnostic(0x95);
+        assert_eq!(value.0, 0x95);
+        
+        let value = ParityErrorReg(0x95);
+        assert_eq!(value.diagnostic(), 0x95);
+    }
+    
+    #[test]
+    fn tag_lo_reg() {
+        let value = TagLoReg(0)
+            .with_pstate(0x3)
+            .with_ptaglo(0xD5555);
+        assert_eq!(value.0, 0xD5555C0);
+        
+        let value = TagLoReg(0xD5555C0);
+        assert_eq!(value.pstate(), 0x3);
+        assert_eq!(value.ptaglo(), 0xD5555);
+    }
+    
+    #[test]
+    fn error_exception_pc_reg() {
+        let value = ErrorExceptionPcReg(0)
+            .with_epc_u32(0x95555555);
+        assert_eq!(value.0, 0x95555555);
+        
+        let value = ErrorExceptionPcReg(0x95555555);
+        assert_eq!(value.epc_u32(), 0x95555555);
+        assert_eq!(value.epc_u64(), 0x95555555);
+    }
+}
+
+mod cp1 {
+    use n64_pac::cp1::{ControlStatusReg, FpuFlags, ImplementationRevisionReg, RoundingMode};
+    
+    #[test]
+    fn implementation_revision_reg() {
+        let value = ImplementationRevisionReg(0xD595);
+        assert_eq!(value.revision(), 0x95);
+        assert_eq!(value.implementation(), 0xD5);
+    }
+    
+    #[test]
+    fn control_status_reg() {
+        let value = ControlStatusReg(0)
+            .with_rm(RoundingMode::Rp)
+            .with_flags(0x15)
+            .with_enables(0x15)
+            .with_causes(0x35)
+            .with_c(true)
+            .with_fs(true);
+        assert_eq!(value.0, 0x1835AD6);
+        
+        let value = ControlStatusReg(0x1835AD6);
+        assert_eq!(value.rm(), RoundingMode::Rp);
+        assert_eq!(value.flags(), 0x15);
+        assert!(value.flag_inexact());
+        assert!(!value.flag_underflow());
+        assert!(value.flag_overflow());
+        assert!(!value.flag_divzero());
+        assert!(value.flag_invalid());
+        assert_eq!(value.enables(), 0x15);
+        assert!(value.enable_inexact());
+        assert!(!value.enable_underflow());
+        assert!(value.enable_overflow());
+        assert!(!value.enable_divzero());
+        assert!(value.enable_invalid());
+        assert_eq!(value.causes(), 0x35);
+        assert!(value.cause_inexact());
+        assert!(!value.cause_underflow());
+        assert!(value.cause_overflow());
+        assert!(!value.cause_divzero());
+        assert!(value.cause_invalid());
+        assert!(value.cause_unimplemented());
+        assert!(value.c());
+        assert!(value.fs());
+    }
+    
+    #[test]
+    fn fpu_flags() {
+        let value = FpuFlags(0)
+            .with_inexact(true)
+            .with_overflow(true)
+            .with_invalid(true);
+        assert_eq!(value.0, 0x15);
+        assert!(value.any());
+        assert!(!FpuFlags(0).any());
+        
+        let value = FpuFlags(ControlStatusReg(0x1835AD6).flags());
+        assert!(value.inexact());
+        assert!(!value.underflow());
+        assert!(value.overflow());
+        assert!(!value.divzero());
+        assert!(value.invalid());
+    }
+}
+
+mod vi {
+    use n64_pac::vi::{rgba16, rgba32, AntiAliasMode, BurstReg, ColorDepth, CtrlReg, HSyncLeapReg, HSyncReg, HVideoReg, VBurstReg, ViConfigError, VVideoReg, XScaleReg, YScaleReg};
+    
+    #[test]
+    fn color_packing() {
+        assert_eq!(rgba16(0xFF, 0x00, 0x00, true), 0xF801);
+        assert_eq!(rgba16(0xFF, 0xFF, 0xFF, true), 0xFFFF);
+        assert_eq!(rgba16(0x08, 0x10, 0x18, false), 0x0886);
+        assert_eq!(rgba32(0x12, 0x34, 0x56, 0x78), 0x12345678);
+    }
+    
+    #[test]
+    fn ctrl_reg() {
+        let value = CtrlReg(0)
+            .with_depth(ColorDepth::BPP32)
+            .with_gamma_dither_enable(true)
+            .with_gamma_enable(true)
+            .with_divot_enable(true)
+            .with_vbus_clock_enable(true)
+            .with_serrate(true)
+            .with_test_mode(true)
+            .with_aa_mode(AntiAliasMode::ResamplingOnly)
+            .with_kill_we(true)
+            .with_pixel_advance(0xD)
+            .with_dither_filter_enable(true);
+        assert_eq!(value.0, 0x1DAFF);
+        
+        let value = CtrlReg(0x1DAFF);
+        assert_eq!(value.depth(), ColorDepth::BPP32);
+        assert!(value.gamma_dither_enable());
+        assert!(value.gamma_enable());
+        assert!(value.divot_enable());
+        assert!(value.vbus_clock_enable());
+        assert!(value.serrate());
+        assert!(value.test_mode());
+        assert_eq!(value.aa_mode(), AntiAliasMode::ResamplingOnly);
+        assert!(value.kill_we());
+        assert_eq!(value.pixel_advance(), 0xD);
+        assert!(value.dither_filter_enable());
+    }
+    
+    #[test]
+    fn ctrl_reg_validate() {
+        let base = CtrlReg(0).with_depth(ColorDepth::BPP16).with_aa_mode(AntiAliasMode::Enabled);
+        assert_eq!(base.validate(), Ok(()));
+        assert_eq!(base.with_divot_enable(true).with_dither_filter_enable(true).validate(), Ok(()));
+        assert_eq!(CtrlReg(0).with_divot_enable(true).validate(), Ok(()));
+        
+        assert_eq!(base.with_vbus_clock_enable(true).validate(), Err(ViConfigError::VbusClockEnabled));
+        assert_eq!(base.with_depth(ColorDepth::Reserved).validate(), Err(ViConfigError::ReservedDepth));
+        assert_eq!(base.with_depth(ColorDepth::BPP32).with_aa_mode(AntiAliasMode::Disabled).validate(), Err(ViConfigError::ResamplingDisabledAt32Bpp));
+        assert_eq!(base.with_depth(ColorDepth::BPP32).with_dither_filter_enable(true).validate(), Err(ViConfigError::DitherFilterAt32Bpp));
+        assert_eq!(base.with_aa_mode(AntiAliasMode::ResamplingOnly).with_dither_filter_enable(true).validate(), Err(ViConfigError::DitherFilterWithoutAntiAlias));
+        assert_eq!(base.with_aa_mode(AntiAliasMode::ResamplingOnly).with_divot_enable(true).validate(), Err(ViConfigError::DivotWithoutAntiAlias));
+    }
+    
+    #[test]
+    fn burst_reg() {
+        let value = BurstReg(0)
+            .with_hsync_width(0x95)
+            .with_burst_width(0xD5)
+            .with_vsync_width(0x9)
+            .with_burst_start(0x355);
+        assert_eq!(value.0, 0x3559D595);
+        
+        let value = BurstReg(0x3559D595);
+        assert_eq!(value.hsync_width(), 0x95);
+        assert_eq!(value.burst_width(), 0xD5);
+        assert_eq!(value.vsync_width(), 0x9);
+        assert_eq!(value.burst_start(), 0x355);
+    }
+    
+    #[test]
+    fn h_sync_reg() {
+        let value = HSyncReg(0)
+            .with_h_sync(0x955)
+            .with_leap(0x15);
+        assert_eq!(value.0, 0x150955);
+        
+        let value = HSyncReg(0x150955);
+        assert_eq!(value.h_sync(), 0x955);
+        assert_eq!(value.leap(), 0x15);
+    }
+    
+    #[test]
+    fn h_sync_leap_reg() {
+        let value = HSyncLeapReg(0)
+            .with_leap_b(0x255)
+            .with_leap_a(0x355);
+        assert_eq!(value.0, 0x3550255);
+        
+        let value = HSyncLeapReg(0x3550255);
+        assert_eq!(value.leap_b(), 0x255);
+        assert_eq!(value.leap_a(), 0x355);
+    }
+    
+    #[test]
+    fn h_video_reg() {
+        let value = HVideoReg(0)
+            .with_h_end(0x255)
+            .with_h_start(0x355);
+        assert_eq!(value.0, 0x3550255);
+        
+        let value = HVideoReg(0x3550255);
+        assert_eq!(value.h_end(), 0x255);
+        assert_eq!(value.h_start(), 0x355);
+    }
+    
+    #[test]
+    fn v_video_reg() {
+        let value = VVideoReg(0)
+            .with_v_end(0x255)
+            .with_v_start(0x355);
+        assert_eq!(value.0, 0x3550255);
+        
+        let value = VVideoReg(0x3550255);
+        assert_eq!(value.v_end(), 0x255);
+        assert_eq!(value.v_start(), 0x355);
+    }
+    
+    #[test]
+    fn v_burst_reg() {
+        let value = VBurstReg(0)
+            .with_v_burst_end(0x255)
+            .with_v_burst_start(0x355);
+        assert_eq!(value.0, 0x3550255);
+        
+        let value = VBurstReg(0x3550255);
+        assert_eq!(value.v_burst_end(), 0x255);
+        assert_eq!(value.v_burst_start(), 0x355);
+    }
+    
+    #[test]
+    fn x_scale_reg() {
+        let value = XScaleReg(0)
+            .with_x_scale(0x955)
+            .with_x_offset(0xD55);
+        assert_eq!(value.0, 0xD550955);
+        
+        let value = XScaleReg(0xD550955);
+        assert_eq!(value.x_scale(), 0x955);
+        assert_eq!(value.x_offset(), 0xD55);
+    }
+    
+    #[test]
+    fn y_scale_reg() {
+        let value = YScaleReg(0)
+            .with_y_scale(0x955)
+            .with_y_offset(0xD55);
+        assert_eq!(value.0, 0xD550955);
+        
+        let value = YScaleReg(0xD550955);
+        assert_eq!(value.y_scale(), 0x955);
+        assert_eq!(value.y_offset(), 0xD55);
+    }
+}
+
+mod mi {
+    use n64_pac::mi::{InterruptReg, InterruptSet, MaskRegRead, MaskRegWrite, ModeReg, ModeRegRead, ModeRegWrite, VersionReg};
+    
+    #[test]
+    fn mode_reg_read() {
+        let value = ModeRegRead(0x3F5);
+        assert_eq!(value.init_length(), 0x75);
+        assert!(value.init_mode());
+        assert!(value.ebus_test_mode());
+        assert!(value.rdram_register_mode());
+    }
+    
+    #[test]
+    fn mode_reg_write() {
+        let value = ModeRegWrite(0)
+            .with_init_length(0x75);
+        assert_eq!(value.0, 0x75);
+        
+        assert_eq!(ModeRegWrite(0).clear_init_mode().0, 0x80);
+        assert_eq!(ModeRegWrite(0).set_init_mode().0, 0x100);
+        assert_eq!(ModeRegWrite(0).clear_ebus_test_mode().0, 0x200);
+        assert_eq!(ModeRegWrite(0).set_ebus_test_mode().0, 0x400);
+        assert_eq!(ModeRegWrite(0).clear_dp_interrupt().0, 0x800);
+        assert_eq!(ModeRegWrite(0).clear_rdram_register_mode().0, 0x1000);
+        assert_eq!(ModeRegWrite(0).set_rdram_register_mode().0, 0x2000);
+    }
+    
+    #[test]
+    fn mode_reg_write_test_modes() {
+        let current = ModeRegRead(0x0F);
+        assert_eq!(ModeRegWrite::from_test_modes(current, None, None, None).0, 0x0F);
+        assert_eq!(ModeRegWrite::from_test_modes(current, Some(0), None, None).0, 0x80);
+        assert_eq!(ModeRegWrite::from_test_modes(current, Some(0x8F), None, None).0, 0x10F);
+        assert_eq!(ModeRegWrite::from_test_modes(current, None, Some(true), Some(false)).0, 0x140F);
+        assert_eq!(ModeRegWrite::from_test_modes(current, None, Some(false), Some(true)).0, 0x220F);
+        
+        assert!(!ModeRegWrite(0).set_init_mode().clear_ebus_test_mode().is_contradictory());
+        assert!(ModeRegWrite(0).set_init_mode().clear_init_mode().is_contradictory());
+        assert!(ModeRegWrite(0).set_rdram_register_mode().clear_rdram_register_mode().is_contradictory());
+    }
+    
+    #[test]
+    fn version_reg() {
+        let value = VersionReg(0xD595D595);
+        assert_eq!(value.io_version(), 0x95);
+        assert_eq!(value.rac_version(), 0xD5);
+        assert_eq!(value.rdp_version(), 0x95);
+        assert_eq!(value.rsp_version(), 0xD5);
+    }
+    
+    #[test]
+    fn interrupt_reg() {
+        let value = InterruptReg(0x3F);
+        assert!(value.sp());
+        assert!(value.si());
+        assert!(value.ai());
+        assert!(value.vi());
+        assert!(value.pi());
+        assert!(value.dp());
+    }
+    
+    #[test]
+    fn mask_reg_read() {
+        let value = MaskRegRead(0x3F);
+        assert!(value.sp_interrupt_mask());
+        assert!(value.si_interrupt_mask());
+        assert!(value.ai_interrupt_mask());
+        assert!(value.vi_interrupt_mask());
+        assert!(value.pi_interrupt_mask());
+        assert!(value.dp_interrupt_mask());
+    }
+    
+    #[test]
+    fn mask_reg_write() {
+        assert_eq!(MaskRegWrite(0).clear_sp_mask().0, 0x1);
+        assert_eq!(MaskRegWrite(0).set_sp_mask().0, 0x2);
+        assert_eq!(MaskRegWrite(0).clear_si_mask().0, 0x4);
+        assert_eq!(MaskRegWrite(0).set_si_mask().0, 0x8);
+        assert_eq!(MaskRegWrite(0).clear_ai_mask().0, 0x10);
+        assert_eq!(MaskRegWrite(0).set_ai_mask().0, 0x20);
+        assert_eq!(MaskRegWrite(0).clear_vi_mask().0, 0x40);
+        assert_eq!(MaskRegWrite(0).set_vi_mask().0, 0x80);
+        assert_eq!(MaskRegWrite(0).clear_pi_mask().0, 0x100);
+        assert_eq!(MaskRegWrite(0).set_pi_mask().0, 0x200);
+        assert_eq!(MaskRegWrite(0).clear_dp_mask().0, 0x400);
+        assert_eq!(MaskRegWrite(0).set_dp_mask().0, 0x800);
+        
+        assert_eq!(MaskRegWrite(0).set_masks(InterruptSet::SP | InterruptSet::VI | InterruptSet::DP).0, 0x882);
+        assert_eq!(MaskRegWrite(0).clear_masks(InterruptSet::SI | InterruptSet::PI).0, 0x104);
+    }
+    
+    #[test]
+    fn union_conversions() {
+        let reg = ModeReg::from(ModeRegWrite(0).set_init_mode());
+        assert_eq!(reg.decoded(), ModeRegRead(0x100));
+        assert_eq!(ModeRegRead::from(reg), ModeRegRead(0x100));
+    }
+    
+    #[test]
+    fn mode_reg_write_bits() {
+        let value = ModeRegRead(!0x3FF);
+        assert_eq!(value.init_length(), 0);
+        assert!(!value.init_mode() && !value.ebus_test_mode() && !value.rdram_register_mode());
+        
+        super::assert_write_bits(&[
+            ModeRegWrite(0).clear_init_mode().0,
+            ModeRegWrite(0).set_init_mode().0,
+            ModeRegWrite(0).clear_ebus_test_mode().0,
+            ModeRegWrite(0).set_ebus_test_mode().0,
+            ModeRegWrite(0).clear_dp_interrupt().0,
+            ModeRegWrite(0).clear_rdram_register_mode().0,
+            ModeRegWrite(0).set_rdram_register_mode().0,
+        ], 0x3F80);
+        assert_eq!(ModeRegWrite(0).with_init_length(0x7F).0 & 0x3F80, 0);
+    }
+    
+    #[test]
+    fn mask_reg_write_bits() {
+        assert!(MaskRegRead(!0x3F).sources().is_empty());
+        
+        super::assert_write_bits(&[
+            MaskRegWrite(0).clear_sp_mask().0,
+            MaskRegWrite(0).set_sp_mask().0,
+            MaskRegWrite(0).clear_si_mask().0,
+            MaskRegWrite(0).set_si_mask().0,
+            MaskRegWrite(0).clear_ai_mask().0,
+            MaskRegWrite(0).set_ai_mask().0,
+            MaskRegWrite(0).clear_vi_mask().0,
+            MaskRegWrite(0).set_vi_mask().0,
+            MaskRegWrite(0).clear_pi_mask().0,
+            MaskRegWrite(0).set_pi_mask().0,
+            MaskRegWrite(0).clear_dp_mask().0,
+            MaskRegWrite(0).set_dp_mask().0,
+        ], 0xFFF);
+    }
+}
+
+mod pi {
+    use n64_pac::pi::{StatusRegRead, StatusRegWrite};
+    
+    #[test]
+    fn status_reg_read() {
+        let value = StatusRegRead(0xF);
+        assert!(value.dma_busy());
+        assert!(value.io_busy());
+        assert!(value.dma_error());
+        assert!(value.interrupt());
+    }
+    
+    #[test]
+    fn status_reg_write() {
+        assert_eq!(StatusRegWrite(0).clear_interrupt().0, 0x1);
+        assert_eq!(StatusRegWrite(0).reset_dma().0, 0x2);
+    }
+    
+    #[test]
+    fn status_reg_write_bits() {
+        let value = StatusRegRead(!0xF);
+        assert!(!value.dma_busy() && !value.io_busy() && !value.dma_error() && !value.interrupt());
+        
+        super::assert_write_bits(&[
+            StatusRegWrite(0).clear_interrupt().0,
+            StatusRegWrite(0).reset_dma().0,
+        ], 0x3);
+    }
+}
+
+mod ri {
+    use n64_pac::ri::{LatencyReg, RefreshReg};
+    
+    #[test]
+    fn refresh_reg() {
+        let value = RefreshReg(0)
+            .with_clean_delay(0x95)
+            .with_dirty_delay(0xD5)
+            .with_bank(true)
+            .with_enable(true)
+            .with_optimize(true)
+            .with_multibank(0xD);
+        assert_eq!(value.0, 0x6FD595);
+        
+        let value = RefreshReg(0x6FD595);
+        assert_eq!(value.clean_delay(), 0x95);
+        assert_eq!(value.dirty_delay(), 0xD5);
+        assert!(value.bank());
+        assert!(value.enable());
+        assert!(value.optimize());
+        assert_eq!(value.multibank(), 0xD);
+    }
+    
+    #[test]
+    fn latency_reg() {
+        let value = LatencyReg(0)
+            .with_latency(0x9);
+        assert_eq!(value.0, 0x9);
+        
+        let value = LatencyReg(0x9);
+        assert_eq!(value.latency(), 0x9);
+    }
+}
+
+mod si {
+    use n64_pac::si::{DmaState, PchState, StatusReg};
+    
+    #[test]
+    fn status_reg() {
+        let value = StatusReg(0)
+            .with_interrupt(true);
+        assert_eq!(value.0, 0x1000);
+        assert_eq!(StatusReg(0).with_whole_register(0x1000).0, 0x1000);
+        
+        let value = StatusReg(0x19DF);
+        assert!(value.dma_busy());
+        assert!(value.io_busy());
+        assert!(value.read_pending());
+        assert!(value.dma_error());
+        assert_eq!(value.pch_state(), 0xD);
+        assert_eq!(value.dma_state(), 0x9);
+        assert_eq!(value.pch_state_decoded(), PchState::Unknown(0xD));
+        assert_eq!(value.dma_state_decoded(), DmaState::Unknown(0x9));
+        assert!(value.interrupt());
+        
+        assert_eq!(StatusReg(0).pch_state_decoded(), PchState::Idle);
+        assert_eq!(StatusReg(0x600).dma_state_decoded(), DmaState::WaitingForAck);
+    }
+}
+
+mod ai {
+    use n64_pac::ai::StatusReg;
+    
+    #[test]
+    fn status_reg() {
+        assert_eq!(StatusReg(0).with_clear_interrupt(0xFFFFFFFF).0, 0xFFFFFFFF);
+        
+        let value = StatusReg(0x4EE94AAB);
+        assert!(value.full());
+        assert_eq!(value.dac_cntr(), 0x2555);
+        assert!(value.bitclock_state());
+        assert!(value.abus_word_2());
+        assert!(value.word_select());
+        assert!(value.data_available());
+        assert!(value.dfifo2_loaded());
+        assert!(value.dma_enable());
+        assert!(value.dma_request());
+        assert!(value.dma_busy());
+        assert!(value.busy());
+    }
+}
+
+mod sp {
+    use n64_pac::sp::{LenReg, MemAddrReg, StatusRegRead, StatusRegWrite};
+    
+    #[test]
+    fn mem_addr_reg() {
+        let value = MemAddrReg(0)
+            .with_addr(0x955)
+            .with_imem(true);
+        assert_eq!(value.0, 0x1955);
+        
+        let value = MemAddrReg(0x1955);
+        assert_eq!(value.addr(), 0x955);
+        assert!(value.imem());
+    }
+    
+    #[test]
+    fn len_reg() {
+        let value = LenReg(0)
+            .with_length(0x955)
+            .with_count(0xD5)
+            .with_skip(0x955);
+        assert_eq!(value.0, 0x955D5955);
+        
+        let value = LenReg(0x955D5955);
+        assert_eq!(value.length(), 0x955);
+        assert_eq!(value.count(), 0xD5);
+        assert_eq!(value.skip(), 0x955);
+    }
+    
+    #[test]
+    fn status_reg_read() {
+        let value = StatusRegRead(0x6AFF);
+        assert!(value.halted());
+        assert!(value.broke());
+        assert!(value.dma_busy());
+        assert!(value.dma_full());
+        assert!(value.io_full());
+        assert!(value.single_step());
+        assert!(value.interrupt_on_break());
+        assert_eq!(value.signals(), 0xD5);
+    }
+    
+    #[test]
+    fn status_reg_write() {
+        assert_eq!(StatusRegWrite(0).clear_halt().0, 0x1);
+        assert_eq!(StatusRegWrite(0).set_halt().0, 0x2);
+        assert_eq!(StatusRegWrite(0).clear_broke().0, 0x4);
+        assert_eq!(StatusRegWrite(0).clear_interrupt().0, 0x8);
+        assert_eq!(StatusRegWrite(0).set_interrupt().0, 0x10);
+        assert_eq!(StatusRegWrite(0).clear_single_step().0, 0x20);
+        assert_eq!(StatusRegWrite(0).set_single_step().0, 0x40);
+        assert_eq!(StatusRegWrite(0).clear_interrupt_on_break().0, 0x80);
+        assert_eq!(StatusRegWrite(0).set_interrupt_on_break().0, 0x100);
+        assert_eq!(StatusRegWrite(0).clear_signal(0).0, 0x200);
+        assert_eq!(StatusRegWrite(0).set_signal(0).0, 0x400);
+        assert_eq!(StatusRegWrite(0).clear_signal(7).0, 0x800000);
+        assert_eq!(StatusRegWrite(0).set_signal(7).0, 0x1000000);
+        assert_eq!(StatusRegWrite(0).clear_signals().0, 0xAAAA00);
+    }
+    
+    #[test]
+    fn status_reg_write_bits() {
+        let value = StatusRegRead(!0x7FFF);
+        assert!(!value.halted() && !value.broke() && !value.dma_busy() && !value.dma_full());
+        assert!(!value.io_full() && !value.single_step() && !value.interrupt_on_break());
+        assert_eq!(value.signals(), 0);
+        
+        let mut bits = vec![
+            StatusRegWrite(0).clear_halt().0,
+            StatusRegWrite(0).set_halt().0,
+            StatusRegWrite(0).clear_broke().0,
+            StatusRegWrite(0).clear_interrupt().0,
+            StatusRegWrite(0).set_interrupt().0,
+            StatusRegWrite(0).clear_single_step().0,
+            StatusRegWrite(0).set_single_step().0,
+            StatusRegWrite(0).clear_interrupt_on_break().0,
+            StatusRegWrite(0).set_interrupt_on_break().0,
+        ];
+        for n in 0..8 {
+            bits.push(StatusRegWrite(0).clear_signal(n).0);
+            bits.push(StatusRegWrite(0).set_signal(n).0);
+        }
+        super::assert_write_bits(&bits, 0x1FFFFFF);
+    }
+}
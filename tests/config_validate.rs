@@ -0,0 +1,20 @@
+//! Tests for validating interface configurations before they are applied.
+
+use n64_pac::ai::{self, AiConfigError};
+use n64_pac::pi::{DomainConfig, PiError};
+
+#[test]
+fn pi_domain_config() {
+    assert_eq!(DomainConfig::SLOWEST.validate(), Ok(()));
+    assert_eq!(DomainConfig { page_size: 0x10, ..DomainConfig::SLOWEST }.validate(), Err(PiError::InvalidConfig));
+    assert_eq!(DomainConfig { release: 0x4, ..DomainConfig::SLOWEST }.validate(), Err(PiError::InvalidConfig));
+}
+
+#[test]
+fn ai_config() {
+    let config = ai::Config { dac_rate: 0x3FFF, bit_rate: 0xF, dma_enable: true };
+    assert_eq!(config.validate(), Ok(()));
+    assert_eq!(ai::Config { dac_rate: 0, ..config }.validate(), Err(AiConfigError::DacRateOutOfRange));
+    assert_eq!(ai::Config { dac_rate: 0x4000, ..config }.validate(), Err(AiConfigError::DacRateOutOfRange));
+    assert_eq!(ai::Config { bit_rate: 0x10, ..config }.validate(), Err(AiConfigError::BitRateOutOfRange));
+}
@@ -0,0 +1,19 @@
+//! Tests for formatting CPU exception information.
+
+use n64_pac::cp0::{BadVAddrReg, CauseReg, ContextReg, EntryHiReg, ExceptionInfo, ExceptionPcReg, StatusReg};
+
+#[test]
+fn exception_info_display() {
+    let info = ExceptionInfo {
+        cause: CauseReg(0x80000008),
+        status: StatusReg(0),
+        epc: ExceptionPcReg(0xFFFFFFFF80001234),
+        badvaddr: BadVAddrReg(0xFFFFFFFFDEAD0000),
+        context: ContextReg(0),
+        entry_hi: EntryHiReg(3),
+    };
+    assert_eq!(info.to_string(), "TLB miss on load @ EPC=0x80001234, bad=0xDEAD0000, asid=3, in delay slot");
+    
+    let info = ExceptionInfo { cause: CauseReg(0x24), ..info };
+    assert_eq!(info.to_string(), "Breakpoint @ EPC=0x80001234");
+}
@@ -0,0 +1,89 @@
+//! Tests for the joybus command encoding and response decoding.
+
+use n64_pac::si::joybus::{address_crc, channel_command_block, data_crc, parse_channel_response, Buttons, DeviceType, MouseState, CMD_INFO};
+
+#[test]
+fn device_type() {
+    assert_eq!(DeviceType::from_type_word(0x0500), DeviceType::Controller);
+    assert_eq!(DeviceType::from_type_word(0x0200), DeviceType::Mouse);
+    assert_eq!(DeviceType::from_type_word(0x0001), DeviceType::Vru);
+    assert_eq!(DeviceType::from_type_word(0x0002), DeviceType::Keyboard);
+    assert_eq!(DeviceType::from_type_word(0x1234), DeviceType::Unknown(0x1234));
+}
+
+#[test]
+fn mouse_state() {
+    let state = MouseState::from_bytes([0x80, 0x00, 0xFE, 0x05]);
+    assert!(state.buttons.a());
+    assert!(!state.buttons.b());
+    assert_eq!(state.dx, -2);
+    assert_eq!(state.dy, 5);
+}
+
+#[test]
+fn channel_command() {
+    let mut block = channel_command_block(2, CMD_INFO, 3);
+    assert_eq!(block[..9], [0x00, 0x00, 0x01, 0x03, 0x00, 0xFF, 0xFF, 0xFF, 0xFE]);
+    assert_eq!(block[63], 0x01);
+    
+    block[5..8].copy_from_slice(&[0x05, 0x00, 0x01]);
+    assert_eq!(parse_channel_response(&block, 2, 3), Some(&[0x05, 0x00, 0x01][..]));
+    block[3] |= 0x80;
+    assert_eq!(parse_channel_response(&block, 2, 3), None);
+}
+
+#[test]
+fn address_crc_known_addresses() {
+    // Rumble pak probe and motor addresses
+    assert_eq!(address_crc(0x8000), 0x01);
+    assert_eq!(address_crc(0xC000), 0x1B);
+    assert_eq!(address_crc(0xC01F), 0x1B);
+    assert_eq!(address_crc(0x0000), 0x00);
+}
+
+#[test]
+fn data_crc_known_blocks() {
+    assert_eq!(data_crc(&[0x00; 32]), 0x00);
+    // Rumble pak motor on
+    assert_eq!(data_crc(&[0x01; 32]), 0xEB);
+    assert_eq!(data_crc(&[0x80; 32]), 0xB8);
+    assert_eq!(data_crc(&[0xFF; 32]), 0x0A);
+}
+
+#[test]
+fn buttons() {
+    let value = Buttons(0)
+        .with_c_right(true)
+        .with_c_left(true)
+        .with_c_down(true)
+        .with_c_up(true)
+        .with_r(true)
+        .with_l(true)
+        .with_reset(true)
+        .with_d_right(true)
+        .with_d_left(true)
+        .with_d_down(true)
+        .with_d_up(true)
+        .with_start(true)
+        .with_z(true)
+        .with_b(true)
+        .with_a(true);
+    assert_eq!(value.0, 0xFFBF);
+    
+    let value = Buttons(0xFFBF);
+    assert!(value.c_right());
+    assert!(value.c_left());
+    assert!(value.c_down());
+    assert!(value.c_up());
+    assert!(value.r());
+    assert!(value.l());
+    assert!(value.reset());
+    assert!(value.d_right());
+    assert!(value.d_left());
+    assert!(value.d_down());
+    assert!(value.d_up());
+    assert!(value.start());
+    assert!(value.z());
+    assert!(value.b());
+    assert!(value.a());
+}
@@ -0,0 +1,27 @@
+//! Tests for parsing the cartridge ROM header.
+
+use n64_pac::pi::{RomHeader, ROM_HEADER_LEN};
+
+#[test]
+fn rom_header() {
+    let mut bytes = [0u8; ROM_HEADER_LEN];
+    bytes[0x00..0x10].copy_from_slice(&[0x80, 0x37, 0x12, 0x40, 0x00, 0x00, 0x00, 0x0F, 0x80, 0x00, 0x04, 0x00, 0x00, 0x00, 0x14, 0x4B]);
+    bytes[0x10..0x18].copy_from_slice(&[0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]);
+    bytes[0x20..0x34].copy_from_slice(b"TEST ROM            ");
+    bytes[0x3B..0x40].copy_from_slice(b"NTSE\x01");
+    
+    let header = RomHeader::parse(&bytes);
+    assert!(header.is_native_byte_order());
+    assert_eq!(header.dom1_release, 3);
+    assert_eq!(header.dom1_page_size, 7);
+    assert_eq!(header.dom1_pulse_width, 0x12);
+    assert_eq!(header.dom1_latency, 0x40);
+    assert_eq!(header.clock_rate, 0x0000000F);
+    assert_eq!(header.entry_point, 0x80000400);
+    assert_eq!(header.release, 0x0000144B);
+    assert_eq!(header.checksum, [0x12345678, 0x9ABCDEF0]);
+    assert_eq!(header.title_str(), Some("TEST ROM"));
+    assert_eq!(&header.game_code, b"NTSE");
+    assert_eq!(header.region(), b'E');
+    assert_eq!(header.version, 1);
+}
@@ -0,0 +1,21 @@
+//! Tests for the ROM filesystem index format.
+
+use n64_pac::pi::romfs::{fnv1a, EntryCount, Layout};
+
+#[test]
+fn fnv1a_hash() {
+    assert_eq!(fnv1a(b""), 0x811C9DC5);
+    assert_eq!(fnv1a(b"a"), 0xE40C292C);
+    assert_eq!(fnv1a(b"foobar"), 0xBF9CF968);
+}
+
+#[test]
+fn parse_entry() {
+    let layout = Layout::new(0x10100000);
+    assert_eq!(layout.count, EntryCount::Prefixed);
+    let entry = [0x12, 0x34, 0x56, 0x78, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+    assert_eq!(layout.parse_entry(&entry), (0x12345678, 0x10200000, 0x100));
+    
+    let layout = Layout { hash_offset: 8, data_offset: 0, len_offset: 4, data_base: 0x10100000, ..layout };
+    assert_eq!(layout.parse_entry(&entry), (0x100, 0x22445678, 0x200000));
+}
@@ -0,0 +1,31 @@
+//! Tests for the VI display region helpers.
+
+use n64_pac::vi::{action_safe_region, aspect_correct_region, safe_area, Rect, VideoMode};
+
+#[test]
+fn safe_areas() {
+    let area = safe_area(320, 240);
+    assert_eq!(area.action, Rect { x: 16, y: 12, width: 288, height: 216 });
+    assert_eq!(area.title, Rect { x: 32, y: 24, width: 256, height: 192 });
+    assert!(area.title.contains(32, 24));
+    assert!(!area.title.contains(288, 24));
+    
+    let (h_video, v_video) = action_safe_region(VideoMode::Ntsc.h_video(), VideoMode::Ntsc.v_video());
+    assert_eq!((h_video.h_start(), h_video.h_end()), (0x6C + 32, 0x2EC - 32));
+    assert_eq!((v_video.v_start(), v_video.v_end()), (0x25 + 22, 0x1FF - 22));
+}
+
+#[test]
+fn aspect_correct() {
+    let (h_video, v_video) = (VideoMode::Ntsc.h_video(), VideoMode::Ntsc.v_video());
+    assert_eq!(aspect_correct_region(h_video, v_video, 320, 240), (h_video, v_video));
+    assert_eq!(aspect_correct_region(h_video, v_video, 640, 480), (h_video, v_video));
+    
+    let (h, v) = aspect_correct_region(h_video, v_video, 240, 240);
+    assert_eq!((h.h_start(), h.h_end()), (188, 668));
+    assert_eq!(v, v_video);
+    
+    let (h, v) = aspect_correct_region(h_video, v_video, 400, 200);
+    assert_eq!(h, h_video);
+    assert_eq!((v.v_start(), v.v_end()), (115, 431));
+}
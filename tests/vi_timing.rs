@@ -0,0 +1,14 @@
+//! Tests for the VI clock rate of each video standard.
+
+use n64_pac::vi::VideoMode;
+
+#[test]
+fn vi_clocks() {
+    for mode in VideoMode::ALL {
+        assert_eq!(VideoMode::from_vi_clock_hz(mode.vi_clock_hz()), Some(mode));
+    }
+    assert_eq!(VideoMode::from_vi_clock_hz(48_690_000), Some(VideoMode::Ntsc));
+    assert_eq!(VideoMode::from_vi_clock_hz(48_620_000), Some(VideoMode::Mpal));
+    assert_eq!(VideoMode::from_vi_clock_hz(49_640_000), Some(VideoMode::Pal));
+    assert_eq!(VideoMode::from_vi_clock_hz(45_000_000), None);
+}
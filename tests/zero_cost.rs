@@ -0,0 +1,91 @@
+//! Layout tests backing the zero-cost register access claim.
+//! 
+//! The free functions (such as `vi::ctrl()`), the wrapper methods (such as
+//! `VideoInterface::new().ctrl.read()`), and `Hardware` all reduce to a single volatile access at a
+//! constant address. That only holds while the register wrappers add nothing to the layout: `RW<T>`
+//! must be exactly a `T`, register blocks must match the hardware offsets, and the interface
+//! structs must be a single pointer. These tests check those properties on the host.
+//! 
+//! The generated code itself can't be checked on the host. When changing the register wrappers or
+//! the `regfn_*` macros, build a release binary for the VR4300 and check its disassembly (such as
+//! with `mips-linux-gnu-objdump -d`) for the following sequences:
+//! 
+//! ```text
+//! vi::ctrl() and VideoInterface::new().ctrl.read() (identical):
+//!     lui   $v0, 0xA440
+//!     lw    $v0, 0x0($v0)
+//! 
+//! vi::modify_ctrl(|ctrl| ctrl.with_serrate(true)) (one load, the modification, one store):
+//!     lui   $a0, 0xA440
+//!     lw    $v0, 0x0($a0)
+//!     ori   $v0, $v0, 0x40
+//!     sw    $v0, 0x0($a0)
+//! ```
+//! 
+//! Register allocation may differ, but any extra loads, stores, or calls indicate a layer which
+//! isn't being optimized away.
+
+use core::mem::{align_of, size_of, MaybeUninit};
+use core::ptr::addr_of;
+use n64_pac::{RegArray, RO, RW, WO};
+use n64_pac::pi::{self, Domain};
+use n64_pac::vi::{CtrlReg, RegisterBlock, VideoInterface};
+
+#[test]
+fn wrappers_are_transparent() {
+    assert_eq!(size_of::<RW<u32>>(), size_of::<u32>());
+    assert_eq!(align_of::<RW<u32>>(), align_of::<u32>());
+    assert_eq!(size_of::<RW<u64>>(), size_of::<u64>());
+    assert_eq!(size_of::<RW<CtrlReg>>(), size_of::<u32>());
+    assert_eq!(size_of::<RO<u32>>(), size_of::<u32>());
+    assert_eq!(size_of::<WO<u32>>(), size_of::<u32>());
+    assert_eq!(size_of::<RegArray<RW<u32>, 4>>(), 4 * size_of::<u32>());
+    assert_eq!(size_of::<RegArray<Domain, 2>>(), 2 * size_of::<Domain>());
+    assert_eq!(size_of::<CtrlReg>(), size_of::<u32>());
+}
+
+#[test]
+fn interface_is_a_pointer() {
+    assert_eq!(size_of::<VideoInterface>(), size_of::<*mut RegisterBlock>());
+}
+
+#[test]
+fn vi_register_block_layout() {
+    let block = MaybeUninit::<RegisterBlock>::uninit();
+    let base = block.as_ptr();
+    let offset = |field: *const u8| field as usize - base as usize;
+    
+    unsafe {
+        assert_eq!(offset(addr_of!((*base).ctrl).cast()), 0x00);
+        assert_eq!(offset(addr_of!((*base).origin).cast()), 0x04);
+        assert_eq!(offset(addr_of!((*base).width).cast()), 0x08);
+        assert_eq!(offset(addr_of!((*base).v_intr).cast()), 0x0C);
+        assert_eq!(offset(addr_of!((*base).v_current).cast()), 0x10);
+        assert_eq!(offset(addr_of!((*base).burst).cast()), 0x14);
+        assert_eq!(offset(addr_of!((*base).v_sync).cast()), 0x18);
+        assert_eq!(offset(addr_of!((*base).h_sync).cast()), 0x1C);
+        assert_eq!(offset(addr_of!((*base).h_sync_leap).cast()), 0x20);
+        assert_eq!(offset(addr_of!((*base).h_video).cast()), 0x24);
+        assert_eq!(offset(addr_of!((*base).v_video).cast()), 0x28);
+        assert_eq!(offset(addr_of!((*base).v_burst).cast()), 0x2C);
+        assert_eq!(offset(addr_of!((*base).x_scale).cast()), 0x30);
+        assert_eq!(offset(addr_of!((*base).y_scale).cast()), 0x34);
+        assert_eq!(offset(addr_of!((*base).test_addr).cast()), 0x38);
+        assert_eq!(offset(addr_of!((*base).staged_data).cast()), 0x3C);
+    }
+    assert_eq!(size_of::<RegisterBlock>(), 0x40);
+}
+
+#[test]
+fn pi_register_block_layout() {
+    let block = MaybeUninit::<pi::RegisterBlock>::uninit();
+    let base = block.as_ptr();
+    let offset = |field: *const u8| field as usize - base as usize;
+    
+    unsafe {
+        assert_eq!(offset(addr_of!((*base).status).cast()), 0x10);
+        assert_eq!(offset(addr_of!((*base).domains).cast()), 0x14);
+    }
+    assert_eq!(size_of::<Domain>(), 0x10);
+    assert_eq!(size_of::<pi::RegisterBlock>(), 0x34);
+}